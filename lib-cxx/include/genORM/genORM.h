@@ -1,9 +1,13 @@
 #pragma once
 #include <functional>
+#include <iterator>
+#include <chrono>
 #include <expected>
 #include <optional>
+#include <utility>
 #include <string>
 #include <variant>
+#include <vector>
 #include <exception>
 #include <stdexcept>
 #include <cstdint>
@@ -11,9 +15,227 @@
 namespace genORM {
 	class database;
 
-	using value_variant = std::variant<std::monostate, int32_t, int64_t, std::vector<uint8_t>>;
+	using value_variant = std::variant<std::monostate, int32_t, int64_t, double, std::string, std::vector<uint8_t>>;
 	using value_binder = std::function<value_variant(int value_index)>;
 
+	enum class error_category {
+		/// Reported by sqlite3 and not covered by a more specific category.
+		database,
+		/// Another connection holds a conflicting lock (SQLITE_BUSY or SQLITE_LOCKED).
+		busy,
+		unique_violation,
+		foreign_key_violation,
+		/// Violation of any other constraint, e.g. NOT NULL or CHECK.
+		constraint_violation,
+		/// The database does not match the generated code, e.g. a table is missing or the schema version is newer.
+		schema_mismatch,
+		/// The database or the connection does not allow writing.
+		read_only,
+		/// A stored value cannot be converted, e.g. an unknown enum value or a malformed timestamp.
+		invalid_value,
+		/// The object no longer exists in the database.
+		not_found,
+		/// The operation is not allowed in the current state, e.g. migrating inside a transaction.
+		misuse,
+		/// Returned by application code, e.g. by the op of a transaction.
+		application
+	};
+
+	struct error {
+		error_category category;
+		/// Primary and extended sqlite3 result codes. Both are SQLITE_OK (0) if the error did not come from sqlite3.
+		int result_code{};
+		int extended_result_code{};
+		/// The sqlite3_errmsg of the connection, or a description of the error.
+		std::string message;
+		/// The statement that failed, empty if the error is not related to a statement.
+		std::string statement{};
+
+		error(const error_category category, std::string message, const int result_code = 0, const int extended_result_code = 0, std::string statement = {})
+			: category(category), result_code(result_code), extended_result_code(extended_result_code), message(std::move(message)), statement(std::move(statement)) {}
+		/// Application errors can be returned as text, e.g. std::unexpected{"Out of stock"}.
+		error(std::string message) : error(error_category::application, std::move(message)) {}
+		error(const char* message) : error(error_category::application, std::string{message}) {}
+
+		/// Returns the message, followed by the failing statement if there is one.
+		[[nodiscard]] std::string to_string() const { return statement.empty() ? message : message + " in statement: " + statement; }
+	};
+
+	[[nodiscard]] inline bool is_unique_violation(const error& e) { return e.category == error_category::unique_violation; }
+	[[nodiscard]] inline bool is_foreign_key_violation(const error& e) { return e.category == error_category::foreign_key_violation; }
+
+	/// Locking behavior of a transaction, see https://www.sqlite.org/lang_transaction.html
+	enum class transaction_mode {
+		/// Locks are acquired on first read or write. Upgrading to a write may fail with SQLITE_BUSY.
+		deferred,
+		/// The write lock is acquired when the transaction begins, other connections can still read.
+		immediate,
+		/// The write lock is acquired when the transaction begins, other connections cannot read either.
+		exclusive
+	};
+
+	/// Handling of SQLITE_BUSY, which is reported when another connection holds a conflicting lock.
+	struct busy_options {
+		/// How long sqlite3 waits for the lock before reporting SQLITE_BUSY. Zero fails immediately.
+		std::chrono::milliseconds timeout{};
+		/// How many times a statement failing with SQLITE_BUSY is retried. Statements that already yielded rows are not
+		/// retried. Inside a deferred transaction, retrying cannot resolve a failed upgrade to a write transaction.
+		int max_retries{};
+		/// Delay before the first retry, doubled for each further retry up to max_backoff.
+		std::chrono::milliseconds initial_backoff{10};
+		std::chrono::milliseconds max_backoff{1000};
+	};
+
+	/// Value of PRAGMA synchronous, see https://www.sqlite.org/pragma.html#pragma_synchronous
+	enum class synchronous_level { off, normal, full, extra };
+
+	/// Options for opening a database. File names may be URIs, e.g. "file:data.db?mode=ro".
+	struct open_options {
+		/// Opens the database without write access. Statements that write fail.
+		bool read_only{};
+		/// Creates the database if it does not exist. Ignored if read_only is set.
+		bool create{};
+		/// Keeps the database in memory, the file name only identifies it for sharing.
+		bool in_memory{};
+		/// Lets connections of the same process with the same file name share a cache, and thus an in-memory database.
+		bool shared_cache{};
+		/// Switches the database to write-ahead logging, which lets readers proceed while another connection writes.
+		bool wal{};
+		/// Left at the sqlite default if not set.
+		std::optional<synchronous_level> synchronous{};
+		bool foreign_keys{true};
+		/// Pragmas executed after opening, in order, e.g. "cache_size = -20000".
+		std::vector<std::string> pragmas{};
+		busy_options busy{};
+	};
+
+	/// Formats the time point as an ISO-8601 UTC timestamp (YYYY-MM-DDTHH:MM:SS.fffZ) with the given number of
+	/// fractional second digits. Timestamps with the same number of digits sort chronologically as text. Years outside
+	/// 0000-9999 are written with a sign and at least four digits, e.g. -0001 or +10000, which do not sort as text.
+	std::string format_timestamp(std::chrono::sys_seconds seconds, std::chrono::nanoseconds subseconds, int fraction_digits);
+	template <typename Duration>
+	std::string format_timestamp(const std::chrono::sys_time<Duration> time, const int fraction_digits) {
+		// Split up at the precision of the time point, converting the whole time point to nanoseconds could overflow
+		const auto seconds = std::chrono::floor<std::chrono::seconds>(time);
+		return format_timestamp(seconds, std::chrono::duration_cast<std::chrono::nanoseconds>(time - seconds), fraction_digits);
+	}
+	/// Parses an ISO-8601 UTC timestamp in the form produced by format_timestamp into whole seconds and the nanoseconds
+	/// within the second.
+	std::expected<std::pair<std::chrono::sys_seconds, std::chrono::nanoseconds>, error> parse_timestamp_parts(std::string_view text);
+	/// Parses an ISO-8601 UTC timestamp in the form produced by format_timestamp. Digits beyond the precision of
+	/// Duration are truncated, timestamps that Duration cannot represent are an invalid_value error.
+	template <typename Duration = std::chrono::nanoseconds>
+	std::expected<std::chrono::sys_time<Duration>, error> parse_timestamp(const std::string_view text) {
+		auto parts = parse_timestamp_parts(text);
+		if (not parts) { return std::unexpected{std::move(parts.error())}; }
+		constexpr auto max_seconds = std::chrono::duration_cast<std::chrono::seconds>(Duration::max()) - std::chrono::seconds{1};
+		constexpr auto min_seconds = std::chrono::duration_cast<std::chrono::seconds>(Duration::min()) + std::chrono::seconds{1};
+		if (const auto since_epoch = parts->first.time_since_epoch(); since_epoch < min_seconds || max_seconds < since_epoch) {
+			return std::unexpected{error{error_category::invalid_value, "Timestamp out of range: " + std::string{text}}};
+		}
+		return std::chrono::time_point_cast<Duration>(parts->first) + std::chrono::duration_cast<Duration>(parts->second);
+	}
+
+	/// A statement that is prepared once per database and reused afterwards. Constructing it registers the text and
+	/// assigns an id, which keys the prepared statement cache of every database. The text must outlive the program, so
+	/// instances are typically function-local statics initialized with a string literal.
+	class cached_statement {
+		size_t _id;
+		std::string_view _text;
+
+	public:
+		explicit cached_statement(std::string_view text);
+
+		[[nodiscard]] size_t get_id() const { return _id; }
+		[[nodiscard]] std::string_view get_text() const { return _text; }
+	};
+
+	/// Steps through the rows of a select statement one at a time. The cursor owns its statement, which holds a read
+	/// lock on the database until the cursor is exhausted or destroyed. It must not outlive its database.
+	class cursor {
+		friend class object;
+		void* _statement_handle;
+		std::vector<value_variant> _column_value_variants;
+		busy_options _busy_options;
+		/// Busy errors are only retried until the first row was read.
+		bool _has_rows{};
+
+		cursor(void* statement_handle, std::vector<value_variant> column_value_variants, const busy_options& busy);
+
+	public:
+		cursor(const cursor& other) = delete;
+		cursor& operator=(const cursor& other) = delete;
+		cursor(cursor&& other) noexcept;
+		cursor& operator=(cursor&& other) noexcept;
+		~cursor();
+
+		/// Returns the next row as a vector of values, or nullopt once all rows were read.
+		std::expected<std::optional<std::vector<value_variant>>, error> next();
+	};
+
+	/// Input range that decodes the rows of a cursor into objects on demand. Each element is either an object or the
+	/// error that occurred while reading or decoding the row, iteration ends after the first error.
+	template <typename T>
+	class object_range {
+	public:
+		using decoder = std::function<std::expected<T, error>(std::vector<value_variant>& row)>;
+
+		class iterator {
+			object_range* _range{};
+
+		public:
+			using value_type = std::expected<T, error>;
+			using difference_type = std::ptrdiff_t;
+
+			iterator() = default;
+			explicit iterator(object_range* range) : _range(range) {}
+
+			value_type& operator*() const { return *_range->_current; }
+			value_type* operator->() const { return &*_range->_current; }
+			iterator& operator++() {
+				_range->advance();
+				return *this;
+			}
+			void operator++(int) { ++*this; }
+			bool operator==(std::default_sentinel_t) const { return not _range->_current; }
+		};
+
+		object_range(cursor rows, decoder decode) : _rows(std::move(rows)), _decode(std::move(decode)) {}
+
+		/// Reads the first row on the first call. The range can only be iterated once.
+		iterator begin() & {
+			if (not _started) {
+				_started = true;
+				advance();
+			}
+			return iterator{this};
+		}
+		[[nodiscard]] std::default_sentinel_t end() const& { return {}; }
+		// Iterators point into the range, a temporary range would leave them dangling
+		iterator begin() && = delete;
+		std::default_sentinel_t end() const&& = delete;
+
+	private:
+		cursor _rows;
+		decoder _decode;
+		std::optional<std::expected<T, error>> _current{};
+		bool _started{};
+
+		void advance() {
+			if (_current && not *_current) {
+				_current.reset();
+				return;
+			}
+			if (auto row = _rows.next(); not row) {
+				_current.emplace(std::unexpected{std::move(row.error())});
+			} else if (not *row) {
+				_current.reset();
+			} else {
+				_current.emplace(_decode(**row));
+			}
+		}
+	};
+
 	class object {
 		database& _db;
 		uint64_t _id;
@@ -24,41 +246,103 @@ namespace genORM {
 	protected:
 		explicit object(database& db, const uint64_t id) : _db(db), _id(id) {}
 
-		static std::expected<void, std::string> create_table_if_not_exists(database&, std::string_view statement);
-		static std::expected<void, std::string> create_index_if_not_exists(database&, std::string_view statement);
-		static std::expected<uint64_t, std::string> insert_into_table(database&, std::string_view statement, int value_count, const value_binder& binder);
+		[[nodiscard]] database& get_database() const { return _db; }
+
+		static std::expected<void, error> create_table_if_not_exists(database&, std::string_view statement);
+		static std::expected<void, error> create_index_if_not_exists(database&, std::string_view statement);
+		static std::expected<uint64_t, error> insert_into_table(database&, const cached_statement& statement, int value_count, const value_binder& binder);
+		/// Returns the number of rows changed by the statement.
+		static std::expected<uint64_t, error> update_table(database&, const cached_statement& statement, int value_count, const value_binder& binder);
+		/// Returns the number of rows deleted by the statement.
+		static std::expected<uint64_t, error> delete_from_table(database&, const cached_statement& statement, int value_count, const value_binder& binder);
 		/// Returns unexpected if an error occurs. Returns nullopt if the statement yielded no rows. Otherwise, returns
 		/// the first row as a vector of values.
-		static std::expected<std::optional<std::vector<value_variant>>, std::string> select_one(database&, std::string_view statement, int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants);
-		static std::expected<std::vector<std::vector<value_variant>>, std::string> select_all(database&, std::string_view statement, int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants);
+		static std::expected<std::optional<std::vector<value_variant>>, error> select_one(database&, const cached_statement& statement, int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants);
+		static std::expected<std::vector<std::vector<value_variant>>, error> select_all(database&, const cached_statement& statement, int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants);
+		/// Binds the values and returns a cursor over the result rows. The statement is prepared separately for each
+		/// cursor, so that several cursors over the same statement can be alive at once.
+		static std::expected<cursor, error> select_cursor(database&, std::string_view statement, int value_count, const value_binder& binder, std::vector<value_variant> column_value_variants);
 	};
 
 	class database final {
 		friend object;
 		void* _db_handle;
+		/// Prepared statements indexed by cached_statement id, null until the statement is first used.
+		std::vector<void*> _prepared_statements;
+		/// Number of transaction scopes entered through execute_transaction. Scopes below the outermost are savepoints.
+		int _transaction_depth{};
+		busy_options _busy_options;
 
-		explicit database(void*);
+		database(void*, const busy_options&);
 
 	public:
-		static std::expected<database, std::string> open(const char* filepath);
-		static std::expected<database, std::string> open_or_create(const char* filepath);
+		static std::expected<database, error> open(const char* filepath, const open_options& options = {});
+		/// Equivalent to open with create set.
+		static std::expected<database, error> open_or_create(const char* filepath, open_options options = {});
 		database(const database& other) = delete;
 		database& operator=(const database& other) = delete;
 		database(database&& other) noexcept;
 		database& operator=(database&& other) noexcept;
 		~database();
 
+		/// Returns the number of statements currently held in the prepared statement cache.
+		[[nodiscard]] size_t get_prepared_statement_count() const;
+
+		/// Executes a statement that takes no values and whose result rows, if any, are discarded.
+		std::expected<void, error> execute(std::string_view statement);
+
+		/// Returns the schema version recorded by migrations in PRAGMA user_version.
+		std::expected<int64_t, error> get_user_version();
+		std::expected<void, error> set_user_version(int64_t version);
+		/// Returns whether foreign key constraints are enforced, see PRAGMA foreign_keys.
+		std::expected<bool, error> get_foreign_keys();
+		std::expected<void, error> set_foreign_keys(bool enabled);
+		/// Returns a foreign_key_violation error for the first row of the table, or of any table if empty, that references
+		/// a missing object. Such rows can only be written while enforcement is off, see PRAGMA foreign_key_check.
+		std::expected<void, error> check_foreign_keys(std::string_view table = {});
+		std::expected<bool, error> has_table(std::string_view table);
+		/// Returns false if either the table or the column does not exist.
+		std::expected<bool, error> has_column(std::string_view table, std::string_view column);
+
+		/// Executes op inside a transaction. The transaction is committed if op succeeds, and rolled back if op returns
+		/// an error or throws. op may also roll back explicitly, in which case an error is returned. Calls can be nested,
+		/// an inner call uses a savepoint whose changes are kept only if the outer transaction commits. The mode only
+		/// applies to the outermost call, savepoints inherit the locks of the enclosing transaction.
 		template <typename RetT>
-		std::expected<RetT, std::string> execute_transaction(const std::function<std::expected<RetT, std::string>(database&)>& op) {
-			if (auto result = begin_transaction(); not result) { return std::unexpected{std::move(result.error())}; }
-			auto retval = op(*this);
-			if (not retval) { return retval; }
-			if (auto result = end_transaction(); not result) { return std::unexpected{std::move(result.error())}; }
+		std::expected<RetT, error> execute_transaction(const std::function<std::expected<RetT, error>(database&)>& op, const transaction_mode mode = transaction_mode::deferred) {
+			if (auto result = begin_transaction(mode); not result) { return std::unexpected{std::move(result.error())}; }
+			const auto depth = _transaction_depth;
+			auto retval = [&] {
+				try {
+					return op(*this);
+				} catch (...) {
+					if (_transaction_depth == depth) { rollback_transaction(); }
+					throw;
+				}
+			}();
+			if (_transaction_depth < depth) { return std::unexpected{error{error_category::application, "Transaction was rolled back"}}; }
+			if (not retval) {
+				rollback_transaction();
+				return retval;
+			}
+			if (auto result = end_transaction(); not result) {
+				rollback_transaction();
+				return std::unexpected{std::move(result.error())};
+			}
 			return retval;
 		}
+		/// Rolls back the innermost transaction scope, which ends the scope. Does nothing if there is no active
+		/// transaction, as sqlite may have already rolled it back after an error.
+		std::expected<void, error> rollback_transaction();
+		/// Returns true if a transaction is active on the connection.
+		[[nodiscard]] bool is_in_transaction() const;
+		/// Returns the number of nested execute_transaction scopes currently active.
+		[[nodiscard]] int get_transaction_depth() const { return _transaction_depth; }
 
 	private:
-		std::expected<void, std::string> begin_transaction();
-		std::expected<void, std::string> end_transaction();
+		/// Returns the prepared statement from the cache, preparing it on first use.
+		std::expected<void*, error> get_prepared_statement(const cached_statement& statement);
+		std::expected<void, error> begin_transaction(transaction_mode mode);
+		std::expected<void, error> end_transaction();
 	};
 }
@@ -1,5 +1,10 @@
 #include <genORM/genORM.h>
 #include <sqlite3.h>
+#include <algorithm>
+#include <atomic>
+#include <cstdio>
+#include <thread>
+#include <tuple>
 
 namespace {
 	template<class... Ts>
@@ -9,36 +14,197 @@ namespace {
 
 	bool op_ignore(void*) { return false; }
 
-	std::expected<void, std::string> prepare_bind_execute_statement(sqlite3* db, const std::string_view statement, const int value_count = 0,
-			const std::function<void(void* sqlite_statement, int value_index)>& binder = binder_none,
-			const std::function<bool(void* sqlite_statement)>& op = op_ignore) {
-		sqlite3_stmt* sqlite_statement{};
-		if (const auto prepare_result = sqlite3_prepare_v2(db, statement.data(), static_cast<int>(statement.size()), &sqlite_statement, nullptr); prepare_result != SQLITE_OK) {
-			return std::unexpected(std::string{sqlite3_errstr(prepare_result)});
+	std::atomic<size_t> next_cached_statement_id{};
+
+	// The connection only holds the details of its most recent failure, which belong to this result if the codes agree
+	genORM::error make_sqlite_error(sqlite3* db, const int result, const std::string_view statement = {}) {
+		const auto primary_result = result & 0xff;
+		const auto last_extended_result = sqlite3_extended_errcode(db);
+		const auto is_last_error = (last_extended_result & 0xff) == primary_result;
+		const auto extended_result = is_last_error ? last_extended_result : result;
+		std::string message = is_last_error ? sqlite3_errmsg(db) : sqlite3_errstr(result);
+		const auto category = primary_result == SQLITE_BUSY || primary_result == SQLITE_LOCKED ? genORM::error_category::busy
+			: extended_result == SQLITE_CONSTRAINT_UNIQUE || extended_result == SQLITE_CONSTRAINT_PRIMARYKEY ? genORM::error_category::unique_violation
+			: extended_result == SQLITE_CONSTRAINT_FOREIGNKEY ? genORM::error_category::foreign_key_violation
+			: primary_result == SQLITE_CONSTRAINT ? genORM::error_category::constraint_violation
+			: primary_result == SQLITE_READONLY ? genORM::error_category::read_only
+			: primary_result == SQLITE_ERROR && (message.starts_with("no such table") || message.starts_with("no such column")) ? genORM::error_category::schema_mismatch
+			: genORM::error_category::database;
+		return genORM::error{category, std::move(message), primary_result, extended_result, std::string{statement}};
+	}
+
+	// Returns false if the retries allowed by the policy are used up. Otherwise, sleeps for the backoff of the attempt.
+	bool wait_before_retry(const genORM::busy_options& busy, const int attempt) {
+		if (busy.max_retries <= attempt) { return false; }
+		auto backoff = busy.initial_backoff;
+		for (int i = 0; i < attempt && backoff < busy.max_backoff; ++i) { backoff *= 2; }
+		std::this_thread::sleep_for(std::min(backoff, busy.max_backoff));
+		return true;
+	}
+
+	int prepare_statement(sqlite3* db, const genORM::busy_options& busy, const std::string_view statement, const unsigned prepare_flags, sqlite3_stmt** sqlite_statement) {
+		int prepare_result;
+		for (int attempt = 0; (prepare_result = sqlite3_prepare_v3(db, statement.data(), static_cast<int>(statement.size()), prepare_flags, sqlite_statement, nullptr)) == SQLITE_BUSY
+				&& wait_before_retry(busy, attempt); ++attempt) {}
+		return prepare_result;
+	}
+
+	// Steps the statement, retrying busy errors according to the policy. Rows already returned cannot be taken back, so
+	// only statements that have not returned a row yet are retried.
+	int step_statement(const genORM::busy_options& busy, sqlite3_stmt* sqlite_statement, const bool has_rows) {
+		int step_result;
+		for (int attempt = 0; (step_result = sqlite3_step(sqlite_statement)) == SQLITE_BUSY && not has_rows && wait_before_retry(busy, attempt); ++attempt) {
+			sqlite3_reset(sqlite_statement);
 		}
+		return step_result;
+	}
 
+	// Binds the values and steps the statement until op returns false or no rows remain. The statement is neither
+	// reset nor finalized.
+	std::expected<void, genORM::error> bind_execute_statement(sqlite3* db, const genORM::busy_options& busy, sqlite3_stmt* sqlite_statement, const int value_count,
+			const std::function<void(void* sqlite_statement, int value_index)>& binder,
+			const std::function<bool(void* sqlite_statement)>& op) {
 		for (int i = 1; i <= value_count; ++i) {
 			binder(sqlite_statement, i);
 		}
 
 		int32_t step_result;
-		while ((step_result = sqlite3_step(sqlite_statement)) == SQLITE_ROW) {
+		bool has_rows = false;
+		while ((step_result = step_statement(busy, sqlite_statement, has_rows)) == SQLITE_ROW) {
+			has_rows = true;
 			if (not op(sqlite_statement)) { break; }
 		}
-		sqlite3_finalize(sqlite_statement);
 		if (step_result != SQLITE_DONE && step_result != SQLITE_ROW) {
-			return std::unexpected(std::string{sqlite3_errstr(step_result)});
+			return std::unexpected(make_sqlite_error(db, step_result, sqlite3_sql(sqlite_statement)));
 		}
 		return {};
 	}
 
+	std::expected<void, genORM::error> prepare_bind_execute_statement(sqlite3* db, const genORM::busy_options& busy, const std::string_view statement, const int value_count = 0,
+			const std::function<void(void* sqlite_statement, int value_index)>& binder = binder_none,
+			const std::function<bool(void* sqlite_statement)>& op = op_ignore) {
+		sqlite3_stmt* sqlite_statement{};
+		if (const auto prepare_result = prepare_statement(db, busy, statement, 0, &sqlite_statement); prepare_result != SQLITE_OK) {
+			return std::unexpected(make_sqlite_error(db, prepare_result, statement));
+		}
+		auto execute_result = bind_execute_statement(db, busy, sqlite_statement, value_count, binder, op);
+		sqlite3_finalize(sqlite_statement);
+		return execute_result;
+	}
+
+	// Tables are created by initialize_schema. Until then, lookups behave as if the tables were empty. A database is
+	// fresh if no migration was recorded and it has no tables at all, in any other state a lookup that fails is reported.
+	bool is_fresh_database(sqlite3* db, const genORM::busy_options& busy) {
+		bool fresh = false;
+		const auto execute_result = prepare_bind_execute_statement(db, busy,
+			"SELECT user_version = 0 AND NOT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%') FROM pragma_user_version;",
+			0, binder_none, [&](void* opaque_sqlite_statement) -> bool {
+				fresh = sqlite3_column_int(static_cast<sqlite3_stmt*>(opaque_sqlite_statement), 0) != 0;
+				return false;
+			});
+		return execute_result && fresh;
+	}
+
+	std::expected<void, genORM::error> bind_execute_cached_statement(sqlite3* db, const genORM::busy_options& busy, const std::expected<void*, genORM::error>& prepared_statement, const int value_count,
+			const std::function<void(void* sqlite_statement, int value_index)>& binder,
+			const std::function<bool(void* sqlite_statement)>& op = op_ignore) {
+		if (not prepared_statement) {
+			return std::unexpected{prepared_statement.error()};
+		}
+		auto* sqlite_statement = static_cast<sqlite3_stmt*>(*prepared_statement);
+		auto execute_result = bind_execute_statement(db, busy, sqlite_statement, value_count, binder, op);
+		// Resetting releases the locks held by the statement, and clearing the bindings drops copies of large values
+		sqlite3_reset(sqlite_statement);
+		sqlite3_clear_bindings(sqlite_statement);
+		return execute_result;
+	}
+
+
 	uint64_t last_insert_rowid(sqlite3* db) {
 		return static_cast<uint64_t>(sqlite3_last_insert_rowid(db));
 	}
 
+	uint64_t changes(sqlite3* db) {
+		return static_cast<uint64_t>(sqlite3_changes64(db));
+	}
+
+	std::optional<int64_t> parse_digits(const std::string_view text, const size_t offset, const size_t count) {
+		if (text.size() < offset + count) { return std::nullopt; }
+		int64_t value = 0;
+		for (size_t i = offset; i < offset + count; ++i) {
+			if (text[i] < '0' || '9' < text[i]) { return std::nullopt; }
+			value = value * 10 + (text[i] - '0');
+		}
+		return value;
+	}
+
+	// Conversions between days since 1970-01-01 and proleptic Gregorian dates, see
+	// https://howardhinnant.github.io/date_algorithms.html. Unlike std::chrono::year, years are not limited to 16 bits.
+	int64_t days_from_civil(int64_t year, const int month, const int day) {
+		year -= month <= 2;
+		const auto era = (0 <= year ? year : year - 399) / 400;
+		const auto year_of_era = year - era * 400;
+		const auto day_of_year = (153 * (month + (2 < month ? -3 : 9)) + 2) / 5 + day - 1;
+		const auto day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+		return era * 146097 + day_of_era - 719468;
+	}
+
+	std::tuple<int64_t, int, int> civil_from_days(int64_t days) {
+		days += 719468;
+		const auto era = (0 <= days ? days : days - 146096) / 146097;
+		const auto day_of_era = days - era * 146097;
+		const auto year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+		const auto day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+		const auto shifted_month = (5 * day_of_year + 2) / 153;
+		const auto day = static_cast<int>(day_of_year - (153 * shifted_month + 2) / 5 + 1);
+		const auto month = static_cast<int>(shifted_month < 10 ? shifted_month + 3 : shifted_month - 9);
+		return {year_of_era + era * 400 + (month <= 2), month, day};
+	}
+
+	int64_t days_in_month(const int64_t year, const int month) {
+		if (month == 2) { return year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) ? 29 : 28; }
+		return month == 4 || month == 6 || month == 9 || month == 11 ? 30 : 31;
+	}
+
+	std::expected<std::vector<genORM::value_variant>, genORM::error> read_row(sqlite3_stmt* sqlite_statement, const std::vector<genORM::value_variant>& column_value_variants) {
+		if (static_cast<size_t>(sqlite3_column_count(sqlite_statement)) != column_value_variants.size()) {
+			return std::unexpected{genORM::error{genORM::error_category::schema_mismatch, "Select result contains different number of columns than expected", SQLITE_OK, SQLITE_OK, sqlite3_sql(sqlite_statement)}};
+		}
+		std::vector<genORM::value_variant> row;
+		for (int i = 0; i < sqlite3_column_count(sqlite_statement); ++i) {
+			if (sqlite3_column_type(sqlite_statement, i) == SQLITE_NULL) {
+				row.emplace_back(std::monostate{});
+			} else {
+				std::visit(overloaded{
+					[](std::monostate) { throw std::runtime_error("Column value variant cannot be monostate"); },
+					[&](const int32_t) {
+						row.emplace_back(sqlite3_column_int(sqlite_statement, i));
+					},
+					[&](const int64_t) {
+						row.emplace_back(sqlite3_column_int64(sqlite_statement, i));
+					},
+					[&](const double) {
+						row.emplace_back(sqlite3_column_double(sqlite_statement, i));
+					},
+					[&](const std::string&) {
+						auto* begin = reinterpret_cast<const char*>(sqlite3_column_text(sqlite_statement, i));
+						auto* end = begin + sqlite3_column_bytes(sqlite_statement, i);
+						row.emplace_back(std::string{begin, end});
+					},
+					[&](const std::vector<uint8_t>&) {
+						auto* begin = static_cast<const uint8_t*>(sqlite3_column_blob(sqlite_statement, i));
+						auto* end = begin + sqlite3_column_bytes(sqlite_statement, i);
+						row.emplace_back(std::vector<uint8_t>{begin, end});
+					},
+				}, column_value_variants[i]);
+			}
+		}
+		return row;
+	}
+
 	struct value_mapper {
 		const genORM::value_binder& binder;
-		std::expected<void, std::string> bind_result{};
+		std::expected<void, genORM::error> bind_result{};
 
 		// This function is repeated called for every value being binded.
 		// `binder` is called with each index, which should return a value_variant.
@@ -51,22 +217,34 @@ namespace {
 			std::visit(overloaded{
 				[&](std::monostate) {
 					if (const auto result = sqlite3_bind_null(sqlite_statement, value_index); result != SQLITE_OK) {
-						bind_result = std::unexpected(std::string{sqlite3_errstr(result)});
+						bind_result = std::unexpected(make_sqlite_error(sqlite3_db_handle(sqlite_statement), result, sqlite3_sql(sqlite_statement)));
 					}
 				},
 				[&](const int32_t i) {
 					if (const auto result = sqlite3_bind_int(sqlite_statement, value_index, i); result != SQLITE_OK) {
-						bind_result = std::unexpected(std::string{sqlite3_errstr(result)});
+						bind_result = std::unexpected(make_sqlite_error(sqlite3_db_handle(sqlite_statement), result, sqlite3_sql(sqlite_statement)));
 					}
 				},
 				[&](const int64_t l) {
 					if (const auto result = sqlite3_bind_int64(sqlite_statement, value_index, l); result != SQLITE_OK) {
-						bind_result = std::unexpected(std::string{sqlite3_errstr(result)});
+						bind_result = std::unexpected(make_sqlite_error(sqlite3_db_handle(sqlite_statement), result, sqlite3_sql(sqlite_statement)));
+					}
+				},
+				[&](const double d) {
+					if (const auto result = sqlite3_bind_double(sqlite_statement, value_index, d); result != SQLITE_OK) {
+						bind_result = std::unexpected(make_sqlite_error(sqlite3_db_handle(sqlite_statement), result, sqlite3_sql(sqlite_statement)));
+					}
+				},
+				[&](const std::string& text) {
+					if (const auto result = sqlite3_bind_text64(sqlite_statement, value_index, text.data(), text.size(), SQLITE_TRANSIENT, SQLITE_UTF8); result != SQLITE_OK) {
+						bind_result = std::unexpected(make_sqlite_error(sqlite3_db_handle(sqlite_statement), result, sqlite3_sql(sqlite_statement)));
 					}
 				},
 				[&](const std::vector<uint8_t>& bytes) {
-					if (const auto result = sqlite3_bind_blob64(sqlite_statement, value_index, bytes.data(), bytes.size(), SQLITE_TRANSIENT); result != SQLITE_OK) {
-						bind_result = std::unexpected(std::string{sqlite3_errstr(result)});
+					// An empty vector may not have a data pointer, which sqlite would bind as NULL
+					if (const auto result = bytes.empty() ? sqlite3_bind_zeroblob(sqlite_statement, value_index, 0)
+							: sqlite3_bind_blob64(sqlite_statement, value_index, bytes.data(), bytes.size(), SQLITE_TRANSIENT); result != SQLITE_OK) {
+						bind_result = std::unexpected(make_sqlite_error(sqlite3_db_handle(sqlite_statement), result, sqlite3_sql(sqlite_statement)));
 					}
 				}
 			}, binder(value_index));
@@ -76,17 +254,73 @@ namespace {
 
 using namespace genORM;
 
-std::expected<void,std::string> object::create_table_if_not_exists(database& db, const std::string_view statement) {
-	return prepare_bind_execute_statement(static_cast<sqlite3*>(db._db_handle), statement);
+std::string genORM::format_timestamp(const std::chrono::sys_seconds seconds, const std::chrono::nanoseconds subseconds, const int fraction_digits) {
+	const auto since_epoch = seconds.time_since_epoch().count();
+	// Floored, so that times before 1970 fall on the previous day
+	const auto days = since_epoch / 86400 - (since_epoch % 86400 < 0);
+	const auto second_of_day = since_epoch - days * 86400;
+	const auto [year, month, day] = civil_from_days(days);
+	char buffer[64];
+	auto length = std::snprintf(buffer, sizeof(buffer), 0 <= year && year <= 9999 ? "%04lld-%02d-%02dT%02d:%02d:%02d" : "%+05lld-%02d-%02dT%02d:%02d:%02d",
+		static_cast<long long>(year), month, day,
+		static_cast<int>(second_of_day / 3600), static_cast<int>(second_of_day / 60 % 60), static_cast<int>(second_of_day % 60));
+	if (0 < fraction_digits) {
+		auto fraction = subseconds.count();
+		for (int i = fraction_digits; i < 9; ++i) { fraction /= 10; }
+		length += std::snprintf(buffer + length, sizeof(buffer) - length, ".%0*lld", fraction_digits, static_cast<long long>(fraction));
+	}
+	return std::string{buffer, static_cast<size_t>(length)} + 'Z';
+}
+
+std::expected<std::pair<std::chrono::sys_seconds, std::chrono::nanoseconds>, error> genORM::parse_timestamp_parts(const std::string_view text) {
+	const auto malformed = [&] { return std::unexpected{error{error_category::invalid_value, "Malformed timestamp: " + std::string{text}}}; };
+	// Years outside 0000-9999 are signed and may have more digits, the remaining fields have fixed widths
+	const bool is_signed = not text.empty() && (text[0] == '+' || text[0] == '-');
+	const auto year_end = text.find('-', is_signed ? 1 : 0);
+	const auto year_digits = year_end == std::string_view::npos ? 0 : year_end - is_signed;
+	if (year_digits < 4 || (not is_signed && year_digits != 4) || 11 < year_digits) {
+		return malformed();
+	}
+	const auto parsed_year = parse_digits(text, is_signed, year_digits);
+	const auto rest = text.substr(year_end);
+	const auto month = parse_digits(rest, 1, 2), day = parse_digits(rest, 4, 2);
+	const auto hours = parse_digits(rest, 7, 2), minutes = parse_digits(rest, 10, 2), seconds = parse_digits(rest, 13, 2);
+	if (not parsed_year || not month || not day || not hours || not minutes || not seconds || rest.size() < 16
+			|| rest[3] != '-' || rest[6] != 'T' || rest[9] != ':' || rest[12] != ':' || rest.back() != 'Z') {
+		return malformed();
+	}
+	int64_t fraction = 0;
+	if (rest.size() != 16) {
+		// Fractional seconds are in between the '.' at index 15 and the trailing 'Z'
+		const auto fraction_digits = rest.size() - 17;
+		const auto parsed_fraction = parse_digits(rest, 16, fraction_digits);
+		if (rest[15] != '.' || fraction_digits == 0 || 9 < fraction_digits || not parsed_fraction) {
+			return malformed();
+		}
+		fraction = *parsed_fraction;
+		for (auto i = fraction_digits; i < 9; ++i) { fraction *= 10; }
+	}
+	const auto year = text[0] == '-' ? -*parsed_year : *parsed_year;
+	if (*month < 1 || 12 < *month || *day < 1 || days_in_month(year, static_cast<int>(*month)) < *day || 23 < *hours || 59 < *minutes || 59 < *seconds) {
+		return std::unexpected{error{error_category::invalid_value, "Invalid timestamp: " + std::string{text}}};
+	}
+	const auto days = days_from_civil(year, static_cast<int>(*month), static_cast<int>(*day));
+	return std::pair{std::chrono::sys_seconds{std::chrono::seconds{days * 86400 + *hours * 3600 + *minutes * 60 + *seconds}}, std::chrono::nanoseconds{fraction}};
 }
 
-std::expected<void, std::string> object::create_index_if_not_exists(database& db, std::string_view statement) {
-	return prepare_bind_execute_statement(static_cast<sqlite3*>(db._db_handle), statement);
+cached_statement::cached_statement(const std::string_view text) : _id(next_cached_statement_id++), _text(text) {}
+
+std::expected<void, error> object::create_table_if_not_exists(database& db, const std::string_view statement) {
+	return prepare_bind_execute_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, statement);
+}
+
+std::expected<void, error> object::create_index_if_not_exists(database& db, std::string_view statement) {
+	return prepare_bind_execute_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, statement);
 }
 
-std::expected<uint64_t, std::string> object::insert_into_table(database& db, const std::string_view statement, const int value_count, const value_binder& binder) {
+std::expected<uint64_t, error> object::insert_into_table(database& db, const cached_statement& statement, const int value_count, const value_binder& binder) {
 	auto mapper = value_mapper{.binder = binder};
-	auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(db._db_handle), statement, value_count, mapper);
+	auto execute_result = bind_execute_cached_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, db.get_prepared_statement(statement), value_count, mapper);
 	if (not mapper.bind_result) {
 		return std::unexpected{std::move(mapper.bind_result.error())};
 	}
@@ -95,132 +329,336 @@ std::expected<uint64_t, std::string> object::insert_into_table(database& db, con
 	}
 	return last_insert_rowid(static_cast<sqlite3*>(db._db_handle));
 }
-std::expected<std::optional<std::vector<value_variant>>, std::string> object::select_one(database& db, const std::string_view statement, const int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants) {
+std::expected<uint64_t, error> object::update_table(database& db, const cached_statement& statement, const int value_count, const value_binder& binder) {
 	auto mapper = value_mapper{.binder = binder};
-	std::expected<std::vector<value_variant>, std::string> return_value{};
-	auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(db._db_handle), statement, value_count, mapper, [&](void* opaque_sqlite_statement) -> bool {
-		auto* sqlite_statement = static_cast<sqlite3_stmt*>(opaque_sqlite_statement);
-		if (static_cast<size_t>(sqlite3_column_count(sqlite_statement)) != column_value_variants.size()) {
-			return_value = std::unexpected{"Select result contains different number of columns than expected"};
-		} else {
-			for (int i = 0; i < sqlite3_column_count(sqlite_statement); ++i) {
-				if (sqlite3_column_type(sqlite_statement, i) == SQLITE_NULL) {
-					return_value.value().emplace_back(std::monostate{});
-				} else {
-					std::visit(overloaded{
-						[](std::monostate) { throw std::runtime_error("Column value variant cannot be monostate"); },
-						[&](const int32_t) {
-							return_value.value().emplace_back(sqlite3_column_int(sqlite_statement, i));
-						},
-						[&](const int64_t) {
-							return_value.value().emplace_back(sqlite3_column_int64(sqlite_statement, i));
-						},
-						[&](const std::vector<uint8_t>&) {
-							auto* begin = static_cast<const uint8_t*>(sqlite3_column_blob(sqlite_statement, i));
-							auto* end = begin + sqlite3_column_bytes(sqlite_statement, i);
-							return_value.value().emplace_back(std::vector<uint8_t>{begin, end});
-						},
-					}, column_value_variants[i]);
-				}
-			}
-		}
+	auto execute_result = bind_execute_cached_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, db.get_prepared_statement(statement), value_count, mapper);
+	if (not mapper.bind_result) {
+		return std::unexpected{std::move(mapper.bind_result.error())};
+	}
+	if (not execute_result) {
+		return std::unexpected{std::move(execute_result.error())};
+	}
+	return changes(static_cast<sqlite3*>(db._db_handle));
+}
+std::expected<uint64_t, error> object::delete_from_table(database& db, const cached_statement& statement, const int value_count, const value_binder& binder) {
+	auto mapper = value_mapper{.binder = binder};
+	auto execute_result = bind_execute_cached_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, db.get_prepared_statement(statement), value_count, mapper);
+	if (not mapper.bind_result) {
+		return std::unexpected{std::move(mapper.bind_result.error())};
+	}
+	if (not execute_result) {
+		return std::unexpected{std::move(execute_result.error())};
+	}
+	return changes(static_cast<sqlite3*>(db._db_handle));
+}
+std::expected<std::optional<std::vector<value_variant>>, error> object::select_one(database& db, const cached_statement& statement, const int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants) {
+	auto mapper = value_mapper{.binder = binder};
+	std::optional<std::expected<std::vector<value_variant>, error>> return_value{};
+	auto execute_result = bind_execute_cached_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, db.get_prepared_statement(statement), value_count, mapper, [&](void* opaque_sqlite_statement) -> bool {
+		return_value = read_row(static_cast<sqlite3_stmt*>(opaque_sqlite_statement), column_value_variants);
 		return false; // Stop after one row
 	});
 	if (not mapper.bind_result) {
 		return std::unexpected{std::move(mapper.bind_result.error())};
 	}
 	if (not execute_result) {
+		if (is_fresh_database(static_cast<sqlite3*>(db._db_handle), db._busy_options)) { return std::nullopt; }
 		return std::unexpected{std::move(execute_result.error())};
 	}
-	if (return_value->empty()) {
+	if (not return_value) {
 		return std::nullopt;
 	}
-	return return_value;
+	if (not *return_value) {
+		return std::unexpected{std::move(return_value->error())};
+	}
+	return std::move(**return_value);
 }
-std::expected<std::vector<std::vector<value_variant>>, std::string> object::select_all(database& db, std::string_view statement, int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants) {
+std::expected<std::vector<std::vector<value_variant>>, error> object::select_all(database& db, const cached_statement& statement, int value_count, const value_binder& binder, const std::vector<value_variant>& column_value_variants) {
 	auto mapper = value_mapper{.binder = binder};
-	std::expected<std::vector<std::vector<value_variant>>, std::string> return_value{};
-	auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(db._db_handle), statement, value_count, mapper, [&](void* opaque_sqlite_statement) -> bool {
-		auto* sqlite_statement = static_cast<sqlite3_stmt*>(opaque_sqlite_statement);
-		if (static_cast<size_t>(sqlite3_column_count(sqlite_statement)) != column_value_variants.size()) {
-			return_value = std::unexpected{"Select result contains different number of columns than expected"};
+	std::expected<std::vector<std::vector<value_variant>>, error> return_value{};
+	auto execute_result = bind_execute_cached_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, db.get_prepared_statement(statement), value_count, mapper, [&](void* opaque_sqlite_statement) -> bool {
+		auto row = read_row(static_cast<sqlite3_stmt*>(opaque_sqlite_statement), column_value_variants);
+		if (not row) {
+			return_value = std::unexpected{std::move(row.error())};
 			return false;
-		} else {
-			std::vector<value_variant> row;
-			for (int i = 0; i < sqlite3_column_count(sqlite_statement); ++i) {
-				if (sqlite3_column_type(sqlite_statement, i) == SQLITE_NULL) {
-					row.emplace_back(std::monostate{});
-				} else {
-					std::visit(overloaded{
-						[](std::monostate) { throw std::runtime_error("Column value variant cannot be monostate"); },
-						[&](const int32_t) {
-							row.emplace_back(sqlite3_column_int(sqlite_statement, i));
-						},
-						[&](const int64_t) {
-							row.emplace_back(sqlite3_column_int64(sqlite_statement, i));
-						},
-						[&](const std::vector<uint8_t>&) {
-							auto* begin = static_cast<const uint8_t*>(sqlite3_column_blob(sqlite_statement, i));
-							auto* end = begin + sqlite3_column_bytes(sqlite_statement, i);
-							row.emplace_back(std::vector<uint8_t>{begin, end});
-						},
-					}, column_value_variants[i]);
-				}
-			}
-			return_value.value().emplace_back(std::move(row));
-			return true;
 		}
+		return_value.value().emplace_back(std::move(*row));
+		return true;
 	});
 	if (not mapper.bind_result) {
 		return std::unexpected{std::move(mapper.bind_result.error())};
 	}
 	if (not execute_result) {
+		if (is_fresh_database(static_cast<sqlite3*>(db._db_handle), db._busy_options)) { return std::vector<std::vector<value_variant>>{}; }
 		return std::unexpected{std::move(execute_result.error())};
 	}
 	return return_value;
 }
+std::expected<cursor, error> object::select_cursor(database& db, const std::string_view statement, const int value_count, const value_binder& binder, std::vector<value_variant> column_value_variants) {
+	// Cursors prepare their own statement, cached statements are reset after every use
+	sqlite3_stmt* sqlite_statement{};
+	if (const auto prepare_result = prepare_statement(static_cast<sqlite3*>(db._db_handle), db._busy_options, statement, 0, &sqlite_statement); prepare_result != SQLITE_OK) {
+		auto prepare_error = make_sqlite_error(static_cast<sqlite3*>(db._db_handle), prepare_result, statement);
+		if (is_fresh_database(static_cast<sqlite3*>(db._db_handle), db._busy_options)) { return cursor{nullptr, {}, db._busy_options}; }
+		return std::unexpected{std::move(prepare_error)};
+	}
+	auto mapper = value_mapper{.binder = binder};
+	for (int i = 1; i <= value_count; ++i) {
+		mapper(sqlite_statement, i);
+	}
+	if (not mapper.bind_result) {
+		sqlite3_finalize(sqlite_statement);
+		return std::unexpected{std::move(mapper.bind_result.error())};
+	}
+	return cursor{sqlite_statement, std::move(column_value_variants), db._busy_options};
+}
 
-database::database(void* db_handle) : _db_handle(db_handle) {}
-
-std::expected<database, std::string> database::open(const char* filepath) {
-	sqlite3* db{};
-	if (const auto result = sqlite3_open_v2(filepath, &db, SQLITE_OPEN_READWRITE, nullptr); result == SQLITE_OK) {
-		if (const auto is_read_only = sqlite3_db_readonly(db, "main"); is_read_only == 0) {
-			return database{db};
+cursor::cursor(void* statement_handle, std::vector<value_variant> column_value_variants, const busy_options& busy)
+	: _statement_handle(statement_handle), _column_value_variants(std::move(column_value_variants)), _busy_options(busy) {}
+cursor::cursor(cursor&& other) noexcept : _statement_handle(other._statement_handle), _column_value_variants(std::move(other._column_value_variants)),
+	_busy_options(other._busy_options), _has_rows(other._has_rows) {
+	other._statement_handle = nullptr;
+}
+cursor& cursor::operator=(cursor&& other) noexcept {
+	std::swap(_statement_handle, other._statement_handle);
+	std::swap(_column_value_variants, other._column_value_variants);
+	std::swap(_busy_options, other._busy_options);
+	std::swap(_has_rows, other._has_rows);
+	return *this;
+}
+cursor::~cursor() {
+	// Finalizing releases the read lock held by the statement
+	sqlite3_finalize(static_cast<sqlite3_stmt*>(_statement_handle));
+	_statement_handle = nullptr;
+}
+std::expected<std::optional<std::vector<value_variant>>, error> cursor::next() {
+	if (not _statement_handle) { return std::nullopt; }
+	auto* sqlite_statement = static_cast<sqlite3_stmt*>(_statement_handle);
+	const auto step_result = step_statement(_busy_options, sqlite_statement, _has_rows);
+	if (step_result == SQLITE_ROW) {
+		_has_rows = true;
+		if (auto row = read_row(sqlite_statement, _column_value_variants)) {
+			return std::move(*row);
+		} else {
+			return std::unexpected{std::move(row.error())};
 		}
-		return std::unexpected("Insufficient permissions, database is read-only.");
-	} else {
-		return std::unexpected(std::string{sqlite3_errstr(result)});
 	}
+	// The cursor is exhausted either way, the statement is not needed anymore
+	auto step_error = step_result == SQLITE_DONE ? std::optional<error>{} : make_sqlite_error(sqlite3_db_handle(sqlite_statement), step_result, sqlite3_sql(sqlite_statement));
+	sqlite3_finalize(sqlite_statement);
+	_statement_handle = nullptr;
+	if (step_error) { return std::unexpected{std::move(*step_error)}; }
+	return std::nullopt;
 }
-std::expected<database, std::string> database::open_or_create(const char* filepath) {
+
+database::database(void* db_handle, const busy_options& busy) : _db_handle(db_handle), _busy_options(busy) {}
+
+std::expected<database, error> database::open(const char* filepath, const open_options& options) {
+	const auto flags = SQLITE_OPEN_URI
+		| (options.read_only ? SQLITE_OPEN_READONLY : SQLITE_OPEN_READWRITE)
+		| (not options.read_only && (options.create || options.in_memory) ? SQLITE_OPEN_CREATE : 0)
+		| (options.in_memory ? SQLITE_OPEN_MEMORY : 0)
+		| (options.shared_cache ? SQLITE_OPEN_SHAREDCACHE : 0);
+	// sqlite3 only shares the cache of in-memory databases that are named by a URI
+	const auto path = options.in_memory && not std::string_view{filepath}.starts_with("file:") ? "file:" + std::string{filepath} : std::string{filepath};
 	sqlite3* db{};
-	if (const auto result = sqlite3_open_v2(filepath, &db, SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE, nullptr); result == SQLITE_OK) {
-		if (const auto is_read_only = sqlite3_db_readonly(db, "main"); is_read_only == 0) {
-			return database{db};
+	if (const auto result = sqlite3_open_v2(path.c_str(), &db, flags, nullptr); result != SQLITE_OK) {
+		auto error = make_sqlite_error(db, result);
+		// A handle is allocated even if opening fails
+		sqlite3_close(db);
+		return std::unexpected(std::move(error));
+	}
+	// sqlite3 silently falls back to read-only access if the file is not writable
+	if (not options.read_only && sqlite3_db_readonly(db, "main") != 0) {
+		sqlite3_close(db);
+		return std::unexpected(error{error_category::read_only, "Insufficient permissions, database is read-only."});
+	}
+	sqlite3_busy_timeout(db, static_cast<int>(options.busy.timeout.count()));
+
+	// Foreign key enforcement is off by default and has to be enabled per connection
+	std::vector<std::string> pragmas{options.foreign_keys ? "foreign_keys = ON" : "foreign_keys = OFF"};
+	if (options.wal) {
+		pragmas.emplace_back("journal_mode = WAL");
+	}
+	if (options.synchronous) {
+		constexpr const char* synchronous_values[] = {"OFF", "NORMAL", "FULL", "EXTRA"};
+		pragmas.emplace_back(std::string{"synchronous = "} + synchronous_values[static_cast<int>(*options.synchronous)]);
+	}
+	pragmas.insert(pragmas.end(), options.pragmas.begin(), options.pragmas.end());
+	for (const auto& pragma : pragmas) {
+		if (auto pragma_result = prepare_bind_execute_statement(db, options.busy, "PRAGMA " + pragma + ";"); not pragma_result) {
+			sqlite3_close(db);
+			return std::unexpected{std::move(pragma_result.error())};
 		}
-		return std::unexpected("Insufficient permissions, database is read-only.");
-	} else {
-		return std::unexpected(std::string{sqlite3_errstr(result)});
 	}
+	return database{db, options.busy};
 }
-database::database(database&& other) noexcept : _db_handle(other._db_handle) {
+std::expected<database, error> database::open_or_create(const char* filepath, open_options options) {
+	options.create = true;
+	return open(filepath, options);
+}
+database::database(database&& other) noexcept : _db_handle(other._db_handle), _prepared_statements(std::move(other._prepared_statements)), _transaction_depth(other._transaction_depth), _busy_options(other._busy_options) {
 	other._db_handle = nullptr;
+	other._transaction_depth = 0;
+	other._prepared_statements.clear();
 }
 database& database::operator=(database&& other) noexcept {
 	std::swap(_db_handle, other._db_handle);
+	std::swap(_prepared_statements, other._prepared_statements);
+	std::swap(_transaction_depth, other._transaction_depth);
+	std::swap(_busy_options, other._busy_options);
 	return *this;
 }
 database::~database() {
+	// Unfinalized statements would keep the connection open
+	for (auto* prepared_statement : _prepared_statements) {
+		sqlite3_finalize(static_cast<sqlite3_stmt*>(prepared_statement));
+	}
+	_prepared_statements.clear();
 	if (_db_handle) {
-		sqlite3_close(static_cast<sqlite3*>(_db_handle));
+		// Cursors that are still alive keep the connection open until they are destroyed
+		sqlite3_close_v2(static_cast<sqlite3*>(_db_handle));
 		_db_handle = nullptr;
 	}
 }
 
-std::expected<void, std::string> database::begin_transaction() {
-	return prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), "BEGIN TRANSACTION;");
+size_t database::get_prepared_statement_count() const {
+	return std::ranges::count_if(_prepared_statements, [](const void* prepared_statement) { return prepared_statement != nullptr; });
+}
+
+std::expected<void*, error> database::get_prepared_statement(const cached_statement& statement) {
+	if (_prepared_statements.size() <= statement.get_id()) {
+		_prepared_statements.resize(statement.get_id() + 1, nullptr);
+	}
+	auto& prepared_statement = _prepared_statements[statement.get_id()];
+	if (not prepared_statement) {
+		sqlite3_stmt* sqlite_statement{};
+		if (const auto prepare_result = prepare_statement(static_cast<sqlite3*>(_db_handle), _busy_options, statement.get_text(), SQLITE_PREPARE_PERSISTENT, &sqlite_statement); prepare_result != SQLITE_OK) {
+			return std::unexpected(make_sqlite_error(static_cast<sqlite3*>(_db_handle), prepare_result, statement.get_text()));
+		}
+		prepared_statement = sqlite_statement;
+	}
+	return prepared_statement;
+}
+
+std::expected<void, error> database::execute(const std::string_view statement) {
+	return prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, statement);
+}
+
+std::expected<int64_t, error> database::get_user_version() {
+	int64_t version{};
+	if (auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "PRAGMA user_version;", 0, binder_none, [&](void* opaque_sqlite_statement) -> bool {
+		version = sqlite3_column_int64(static_cast<sqlite3_stmt*>(opaque_sqlite_statement), 0);
+		return false;
+	}); not execute_result) {
+		return std::unexpected{std::move(execute_result.error())};
+	}
+	return version;
+}
+std::expected<void, error> database::set_user_version(const int64_t version) {
+	// Pragmas do not accept bound values
+	return prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "PRAGMA user_version = " + std::to_string(version) + ";");
+}
+std::expected<bool, error> database::get_foreign_keys() {
+	bool enabled{};
+	if (auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "PRAGMA foreign_keys;", 0, binder_none, [&](void* opaque_sqlite_statement) -> bool {
+		enabled = sqlite3_column_int(static_cast<sqlite3_stmt*>(opaque_sqlite_statement), 0) != 0;
+		return false;
+	}); not execute_result) {
+		return std::unexpected{std::move(execute_result.error())};
+	}
+	return enabled;
+}
+std::expected<void, error> database::set_foreign_keys(const bool enabled) {
+	return prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, enabled ? "PRAGMA foreign_keys=ON;" : "PRAGMA foreign_keys=OFF;");
+}
+std::expected<void, error> database::check_foreign_keys(const std::string_view table) {
+	// Pragmas do not accept bound values
+	const auto statement = table.empty() ? std::string{"PRAGMA foreign_key_check;"} : "PRAGMA foreign_key_check(\"" + std::string{table} + "\");";
+	std::optional<std::string> violation;
+	if (auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, statement, 0, binder_none, [&](void* opaque_sqlite_statement) -> bool {
+		auto* stmt = static_cast<sqlite3_stmt*>(opaque_sqlite_statement);
+		// Columns are the referencing table, its rowid and the referenced table
+		violation = "Row " + std::to_string(sqlite3_column_int64(stmt, 1)) + " of table " + reinterpret_cast<const char*>(sqlite3_column_text(stmt, 0))
+			+ " references a missing object of table " + reinterpret_cast<const char*>(sqlite3_column_text(stmt, 2));
+		return false;
+	}); not execute_result) {
+		return std::unexpected{std::move(execute_result.error())};
+	}
+	if (violation) {
+		return std::unexpected{error{error_category::foreign_key_violation, std::move(*violation), SQLITE_CONSTRAINT, SQLITE_CONSTRAINT_FOREIGNKEY, statement}};
+	}
+	return {};
+}
+
+std::expected<bool, error> database::has_table(const std::string_view table) {
+	bool found = false;
+	const value_binder binder = [&](int) -> value_variant { return std::string{table}; };
+	auto mapper = value_mapper{.binder = binder};
+	auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?;", 1, mapper, [&](void*) -> bool {
+		found = true;
+		return false;
+	});
+	if (not mapper.bind_result) {
+		return std::unexpected{std::move(mapper.bind_result.error())};
+	}
+	if (not execute_result) {
+		return std::unexpected{std::move(execute_result.error())};
+	}
+	return found;
+}
+std::expected<bool, error> database::has_column(const std::string_view table, const std::string_view column) {
+	bool found = false;
+	const value_binder binder = [&](const int value_index) -> value_variant { return std::string{value_index == 1 ? table : column}; };
+	auto mapper = value_mapper{.binder = binder};
+	auto execute_result = prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "SELECT 1 FROM pragma_table_info(?) WHERE name = ?;", 2, mapper, [&](void*) -> bool {
+		found = true;
+		return false;
+	});
+	if (not mapper.bind_result) {
+		return std::unexpected{std::move(mapper.bind_result.error())};
+	}
+	if (not execute_result) {
+		return std::unexpected{std::move(execute_result.error())};
+	}
+	return found;
+}
+
+std::expected<void, error> database::begin_transaction(const transaction_mode mode) {
+	const auto begin_statement = mode == transaction_mode::immediate ? "BEGIN IMMEDIATE TRANSACTION;"
+		: mode == transaction_mode::exclusive ? "BEGIN EXCLUSIVE TRANSACTION;"
+		: "BEGIN DEFERRED TRANSACTION;";
+	// sqlite3 cannot nest transactions, inner scopes are savepoints named after their depth
+	auto result = _transaction_depth == 0
+		? prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, begin_statement)
+		: prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "SAVEPOINT __genORM_" + std::to_string(_transaction_depth) + ";");
+	if (result) { ++_transaction_depth; }
+	return result;
+}
+std::expected<void, error> database::end_transaction() {
+	auto result = _transaction_depth <= 1
+		? prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "END TRANSACTION;")
+		: prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "RELEASE __genORM_" + std::to_string(_transaction_depth - 1) + ";");
+	if (result) { _transaction_depth = std::max(_transaction_depth - 1, 0); }
+	return result;
+}
+std::expected<void, error> database::rollback_transaction() {
+	if (not is_in_transaction()) {
+		_transaction_depth = 0;
+		return {};
+	}
+	if (_transaction_depth <= 1) {
+		_transaction_depth = 0;
+		return prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "ROLLBACK TRANSACTION;");
+	}
+	// Rolling back to a savepoint keeps it open, it has to be released as well
+	const auto savepoint = "__genORM_" + std::to_string(_transaction_depth - 1);
+	if (auto result = prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "ROLLBACK TO " + savepoint + ";"); not result) {
+		return result;
+	}
+	--_transaction_depth;
+	return prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), _busy_options, "RELEASE " + savepoint + ";");
 }
-std::expected<void, std::string> database::end_transaction() {
-	return prepare_bind_execute_statement(static_cast<sqlite3*>(_db_handle), "END TRANSACTION;");
+bool database::is_in_transaction() const {
+	return sqlite3_get_autocommit(static_cast<sqlite3*>(_db_handle)) == 0;
 }
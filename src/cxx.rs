@@ -1,7 +1,8 @@
-use crate::{CxxOptions, Member, ObjectType};
+use crate::{CxxOptions, EnumType, Member, Migration, MigrationStep, ObjectType, Relation};
+use crate::util::{is_cxx_keyword, is_identifier};
 use std::fs;
 
-fn generate_header(output_dir: &str, file_prefix: &str, namespace: &str, object_types: &Vec<ObjectType>) -> Result<(), String> {
+fn generate_header(output_dir: &str, file_prefix: &str, namespace: &str, enums: &[EnumType], object_types: &Vec<ObjectType>, migrations: &[Migration]) -> Result<(), String> {
     let headers_and_namespace = format!("\
         // Auto-generated file. Changes will be overridden.\n\
         #pragma once\n\
@@ -9,10 +10,23 @@ fn generate_header(output_dir: &str, file_prefix: &str, namespace: &str, object_
         #include <vector>\n\
         #include <string>\n\
         #include <optional>\n\
+        #include <chrono>\n\
+        #include <bitset>\n\
         \n\
         namespace {namespace} {{\n\
         ");
 
+    let enum_declarations = enums.iter()
+        .map(|e| e.validate(enums).map(|e| e.format_declaration()))
+        .collect::<Result<Vec<String>, String>>()?
+        .join("");
+
+    // Forward declarations let references point at types declared further down
+    let forward_declarations = object_types.iter()
+        .map(|o| format!("    class {};\n", o.name))
+        .collect::<Vec<String>>()
+        .join("") + "\n";
+
     let mut type_declarations = String::new();
     for object_type in object_types {
         let class_name = &object_type.name;
@@ -21,14 +35,20 @@ fn generate_header(output_dir: &str, file_prefix: &str, namespace: &str, object_
         }
 
         let description = object_type.description.as_ref()
-            .map(|s| String::from("    /// ") + &s + "\n")
-            .unwrap_or(String::new());
+            .map(|s| String::from("    /// ") + s + "\n")
+            .unwrap_or_default();
 
         let open_class = format!("    class {class_name} final : public genORM::object {{\n");
 
         let validated_members = object_type.members.iter()
-            .map(|m| { m.validate() })
+            .map(|m| { m.validate(enums, object_types) })
             .collect::<Result<Vec<&dyn MemberExt>, String>>()?;
+        let unique_keys = object_type.unique_keys()?;
+        let composite_indexes = object_type.composite_indexes()?;
+        let relation_declarations = object_type.relations.iter().flatten()
+            .map(|r| r.validate(object_type, object_types).map(|_| r.format_declarations()))
+            .collect::<Result<Vec<String>, String>>()?
+            .join("");
         let member_declarations = validated_members.iter()
             .map(|m| m.format_declaration())
             .collect::<Vec<String>>()
@@ -38,53 +58,130 @@ fn generate_header(output_dir: &str, file_prefix: &str, namespace: &str, object_
             .map(|m| m.format_constructor_parameter())
             .collect::<Vec<String>>()
             .join(", ");
-        let constructor_declaration = format!("        explicit {class_name}(genORM::database& __db, uint64_t __id, {member_constructor_parameters});\n\n");
+        let constructor_declaration = format!("        explicit {class_name}(genORM::database& __db, uint64_t __id, {member_constructor_parameters});\n");
+        let from_row_declaration = format!("        static std::expected<{class_name}, genORM::error> from_row(genORM::database& __db, std::vector<genORM::value_variant>& row);\n");
+        // Owners of many-to-many relations decode rows of this type while navigating the join table
+        let friend_declarations = object_types.iter()
+            .filter(|o| &o.name != class_name && o.relations.iter().flatten().any(|r| r.is_many_to_many() && &r.target == class_name))
+            .map(|o| format!("        friend class {};\n", o.name))
+            .collect::<Vec<String>>()
+            .join("") + "\n";
 
-        let create_declaration = format!("    public:\n        static std::expected<{class_name}, std::string> create(genORM::database& __db, {member_constructor_parameters});\n");
+        let ensure_schema_declaration = "    public:\n        /// Creates the table and indexes of the type, unless they already exist.\n        static std::expected<void, genORM::error> ensure_schema(genORM::database& __db);\n";
+        let create_declaration = format!("        static std::expected<{class_name}, genORM::error> create(genORM::database& __db, {member_constructor_parameters});\n");
 
-        let find_by_rowid_declaration = format!("        static std::expected<std::optional<{class_name}>, std::string> find_by_rowid(genORM::database& __db, uint64_t);\n");
+        let find_by_rowid_declaration = format!("        static std::expected<std::optional<{class_name}>, genORM::error> find_by_rowid(genORM::database& __db, uint64_t);\n");
         let find_by_member_declarations = validated_members.iter()
+            .filter(|m| m.is_index() && !m.is_unique())
+            .map(|m| {
+                let member_name = m.format_name();
+                let member_parameter = m.format_constructor_parameter();
+                format!("        static std::expected<std::optional<{class_name}>, genORM::error> find_first_by_{member_name}(genORM::database& __db, {member_parameter});\n        \
+                    static std::expected<std::vector<{class_name}>, genORM::error> find_all_by_{member_name}(genORM::database& __db, {member_parameter});\n        \
+                    /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in\n        \
+                    /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.\n        \
+                    static std::expected<genORM::object_range<{class_name}>, genORM::error> iterate_all_by_{member_name}(genORM::database& __db, {member_parameter});\n")
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let find_by_unique_key_declarations = unique_keys.iter()
+            .map(|key| {
+                let key_name = format_key_name(key);
+                let key_parameters = format_key_parameters(key, "");
+                format!("        static std::expected<std::optional<{class_name}>, genORM::error> find_by_{key_name}(genORM::database& __db, {key_parameters});\n")
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let find_by_composite_index_declarations = composite_indexes.iter()
+            .map(|key| {
+                let key_name = format_key_name(key);
+                let key_parameters = format_key_parameters(key, "");
+                format!("        static std::expected<std::optional<{class_name}>, genORM::error> find_first_by_{key_name}(genORM::database& __db, {key_parameters});\n        \
+                    static std::expected<std::vector<{class_name}>, genORM::error> find_all_by_{key_name}(genORM::database& __db, {key_parameters});\n")
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let find_between_declarations = validated_members.iter()
+            .filter(|m| m.is_index() && m.is_range_index())
+            .map(|m| {
+                let member_name = m.format_name();
+                let member_type = m.format_timestamp_type();
+                format!("        static std::expected<std::vector<{class_name}>, genORM::error> find_all_by_{member_name}_between(genORM::database& __db, {member_type} __from, {member_type} __to);\n")
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let delete_by_rowid_declaration = "        static std::expected<uint64_t, genORM::error> delete_by_rowid(genORM::database& __db, uint64_t);\n";
+        let delete_by_member_declarations = validated_members.iter()
             .filter(|m| m.is_index())
             .map(|m| {
                 let member_name = m.format_name();
                 let member_parameter = m.format_constructor_parameter();
-                format!("        static std::expected<std::optional<{class_name}>, std::string> find_first_by_{member_name}(genORM::database& __db, {member_parameter});\n        \
-                    static std::expected<std::vector<{class_name}>, std::string> find_all_by_{member_name}(genORM::database& __db, {member_parameter});\n")
+                format!("        static std::expected<uint64_t, genORM::error> delete_all_by_{member_name}(genORM::database& __db, {member_parameter});\n")
             })
             .collect::<Vec<String>>()
             .join("");
 
+        let save_declaration = "        /// Writes the members changed through the setters since the object was read or last saved, other columns keep\n        \
+            /// the values they have in the database.\n        \
+            std::expected<void, genORM::error> save();\n";
+        let remove_declaration = "        std::expected<void, genORM::error> remove() const;\n";
+
         let getter_declarations = validated_members.iter()
             .map(|m| m.format_getter_declaration())
             .collect::<Vec<String>>()
             .join("");
 
-        // let setter_declarations = validated_members.iter()
-        //     .map(|m| m.format_setter_declaration())
-        //     .collect::<Vec<String>>()
-        //     .join("");
+        let setter_declarations = validated_members.iter().enumerate()
+            .map(|(i, m)| m.format_setter_declaration(i))
+            .collect::<Vec<String>>()
+            .join("");
 
-        let close_class = format!("    }};\n");
-        type_declarations.push_str(&format!("{description}{open_class}{member_declarations}\n{constructor_declaration}{create_declaration}{find_by_rowid_declaration}{find_by_member_declarations}\n{getter_declarations}{close_class}\n"));
+        // Members assigned through the setters, save() only writes these
+        let changed_declaration = format!("    private:\n        std::bitset<{}> _changed;\n", validated_members.len());
+        let close_class = "    };\n";
+        type_declarations.push_str(&format!("{description}{open_class}{member_declarations}\n{constructor_declaration}{from_row_declaration}{friend_declarations}{ensure_schema_declaration}{create_declaration}{find_by_rowid_declaration}{find_by_member_declarations}{find_by_unique_key_declarations}{find_by_composite_index_declarations}{find_between_declarations}{delete_by_rowid_declaration}{delete_by_member_declarations}{save_declaration}{remove_declaration}{relation_declarations}\n{getter_declarations}{setter_declarations}{changed_declaration}{close_class}\n"));
     }
 
+    replay_migrations(enums, object_types, migrations)?;
+    let schema_version = migrations.last().map(|m| m.version).unwrap_or_default();
+    let initialize_schema_declaration = "    /// Creates the tables and indexes of every type in a single transaction, which is nested in the\n    \
+        /// transaction of the caller if there is one. Objects can only be created afterwards. Lookups on a database\n    \
+        /// without any tables find nothing, otherwise tables missing from the database are reported as schema_mismatch.\n    \
+        std::expected<void, genORM::error> initialize_schema(genORM::database& __db);\n";
+    let migrate_declaration = format!("    /// Version of the schema described by the migrations of the config.\n    \
+        inline constexpr int64_t schema_version = {schema_version};\n    \
+        /// Brings an existing database up to schema_version, recording progress in PRAGMA user_version. Call it after\n    \
+        /// opening the database, before initialize_schema and outside any transaction.\n    \
+        std::expected<void, genORM::error> migrate(genORM::database& __db);\n");
+
     let close_namespace = "}\n";
 
     fs::write(String::from(output_dir) + file_prefix + ".orm.h",
-              format!("{headers_and_namespace}{type_declarations}{close_namespace}"))
+              format!("{headers_and_namespace}{enum_declarations}{forward_declarations}{type_declarations}{initialize_schema_declaration}{migrate_declaration}{close_namespace}"))
         .map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
-fn generate_source(output_dir: &str, file_prefix: &str, namespace: &str, object_types: &Vec<ObjectType>) -> Result<(), String> {
+fn generate_source(output_dir: &str, file_prefix: &str, namespace: &str, enums: &[EnumType], object_types: &Vec<ObjectType>, migrations: &[Migration]) -> Result<(), String> {
     let include_headers = format!("\
         // Auto-generated file. Changes will be overridden.\n\
         #include \"{file_prefix}.orm.h\"\n\n");
 
+    let enum_definitions = enums.iter()
+        .map(|e| e.format_conversion_implementations(namespace))
+        .collect::<Vec<String>>()
+        .join("");
+
     let mut type_definitions = String::new();
     for object_type in object_types {
         let class_name = &object_type.name;
+        let unique_keys = object_type.unique_keys()?;
+        let composite_indexes = object_type.composite_indexes()?;
 
         let constructor_parameters = object_type.members.iter()
             .map(|m| m.format_constructor_parameter())
@@ -97,29 +194,34 @@ fn generate_source(output_dir: &str, file_prefix: &str, namespace: &str, object_
         let constructor = format!("{namespace}::{class_name}::{class_name}(genORM::database& __db, uint64_t __id, {constructor_parameters})\n    \
             : object(__db, __id), {constructor_implementations} {{}}\n\n");
 
-        let create_table_statement = object_type.members.iter()
-            .map(|m| m.format_database_schema())
-            .collect::<Vec<String>>()
-            .join(", ");
+        let create_table_statement = format_create_table_statement(object_type, enums, class_name);
 
-        let create_index_blocks = object_type.members.iter()
-            .filter(|m| m.is_index())
-            .map(|m| {
-                let member_name = &m.name;
+        let create_index_blocks = format_create_index_statements(object_type)?.iter()
+            .map(|create_index_statement| {
                 format!("    {{\n        \
-                    static constexpr std::string_view create_index_statement = \"CREATE INDEX IF NOT EXISTS Index_{class_name}_{member_name} ON {class_name}({member_name});\";\n        \
+                    static constexpr std::string_view create_index_statement = \"{create_index_statement}\";\n        \
                     if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) {{ return std::unexpected{{std::move(create_index_result.error())}}; }}\n    \
                     }}\n")
             })
+            .chain(object_type.relations.iter().flatten()
+                .filter(|r| r.is_many_to_many())
+                .map(|r| r.format_create_join_table_block(class_name)))
             .collect::<Vec<String>>()
             .join("");
 
+        let ensure_schema_implementation = format!("std::expected<void, genORM::error> {namespace}::{class_name}::ensure_schema(genORM::database& __db) {{\n    \
+            static constexpr std::string_view create_table_statement = \"{create_table_statement}\";\n    \
+            if (auto create_table_result = create_table_if_not_exists(__db, create_table_statement); not create_table_result) {{ return std::unexpected{{std::move(create_table_result.error())}}; }}\n\
+            {create_index_blocks}    \
+            return {{}};\n}}\n");
+
         let insert_statement = vec![String::from("?"); object_type.members.len()].join(", ");
+        let insert_columns = object_type.members.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", ");
 
         let binder_implementation = (1..=object_type.members.len())
             .map(|i| {
                 let member_impl = object_type.members[i - 1].format_binder_implementation();
-                return format!("        if (value_index == {i}) {{ return {member_impl}; }}");
+                format!("        if (value_index == {i}) {{ return {member_impl}; }}")
             }).collect::<Vec<_>>().join("\n") + "\n        throw std::logic_error(\"Implementation error\");\n";
         let member_count = object_type.members.len();
 
@@ -128,11 +230,8 @@ fn generate_source(output_dir: &str, file_prefix: &str, namespace: &str, object_
             .collect::<Vec<_>>()
             .join(", ");
 
-        let create_implementation = format!("std::expected<{namespace}::{class_name}, std::string> {namespace}::{class_name}::create(genORM::database& __db, {constructor_parameters}) {{\n    \
-            static constexpr std::string_view create_table_statement = \"CREATE TABLE IF NOT EXISTS {class_name} (__id INTEGER PRIMARY KEY NOT NULL, {create_table_statement}) STRICT;\";\n    \
-            if (auto create_table_result = create_table_if_not_exists(__db, create_table_statement); not create_table_result) {{ return std::unexpected{{std::move(create_table_result.error())}}; }}\n\
-            {create_index_blocks}    \
-            static constexpr std::string_view insert_statement = \"INSERT INTO {class_name} VALUES (NULL, {insert_statement});\";\n    \
+        let create_implementation = format!("std::expected<{namespace}::{class_name}, genORM::error> {namespace}::{class_name}::create(genORM::database& __db, {constructor_parameters}) {{\n    \
+            static const genORM::cached_statement insert_statement{{\"INSERT INTO {class_name} ({insert_columns}) VALUES ({insert_statement});\"}};\n    \
             const auto binder = [&](int value_index) -> genORM::value_variant {{\n\
             {binder_implementation}    }};\n    \
             if (auto create_table_result = insert_into_table(__db, insert_statement, {member_count}, binder)) {{\n        \
@@ -142,22 +241,34 @@ fn generate_source(output_dir: &str, file_prefix: &str, namespace: &str, object_
             }}\n}}\n");
 
         let column_value_variants = object_type.members.iter()
-            .map(|m| m.format_column_value_variant())
+            .map(|m| m.format_column_value_variant(enums))
             .collect::<Vec<_>>()
             .join(", ");
+        // Columns are listed explicitly, migrations may have added them in a different order
+        let select_columns = format_select_columns(object_type, "");
+
+        let select_result_validation = object_type.members.iter().enumerate()
+            .flat_map(|(index, m)| m.format_select_result_validation("row", (index + 1) as i32))
+            .map(|line| format!("    {line}\n"))
+            .collect::<Vec<_>>()
+            .join("");
 
         let select_result_to_value = object_type.members.iter().enumerate()
-            .map(|(index, m)| m.format_select_result_to_value("(**select_result)", (index + 1) as i32))
+            .map(|(index, m)| m.format_select_result_to_value("row", (index + 1) as i32))
             .collect::<Vec<_>>()
-            .join(",\n                ");
+            .join(",\n        ");
+
+        let from_row_implementation = format!("std::expected<{namespace}::{class_name}, genORM::error> {namespace}::{class_name}::from_row(genORM::database& __db, std::vector<genORM::value_variant>& row) {{\n\
+            {select_result_validation}    \
+            return {class_name}{{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),\n        \
+            {select_result_to_value}\n    }};\n}}\n");
 
-        let find_by_rowid_implementation = format!("std::expected<std::optional<{namespace}::{class_name}>, std::string> {namespace}::{class_name}::find_by_rowid(genORM::database& __db, const uint64_t __id) {{\n    \
-            static constexpr std::string_view select_statement = \"SELECT * FROM {class_name} WHERE __id = ? LIMIT 1;\";\n    \
+        let find_by_rowid_implementation = format!("std::expected<std::optional<{namespace}::{class_name}>, genORM::error> {namespace}::{class_name}::find_by_rowid(genORM::database& __db, const uint64_t __id) {{\n    \
+            static const genORM::cached_statement select_statement{{\"SELECT {select_columns} FROM {class_name} WHERE __id = ? LIMIT 1;\"}};\n    \
             if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant {{ return static_cast<int64_t>(__id); }},\n            \
             std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
             if (*select_result) {{\n            \
-            return {class_name}{{__db, __id,\n                \
-            {select_result_to_value}\n            }};\n        \
+            return from_row(__db, **select_result);\n        \
             }} else {{\n            \
             return std::nullopt;\n        }}\n    \
             }} else {{\n        \
@@ -165,18 +276,18 @@ fn generate_source(output_dir: &str, file_prefix: &str, namespace: &str, object_
             }}\n}}\n");
 
         let find_first_by_implementation = object_type.members.iter()
-            .filter(|m| m.is_index())
+            .filter(|m| m.is_index() && !m.is_unique())
             .map(|m| {
                 let member_name = m.format_name();
                 let member_parameter = m.format_constructor_parameter();
                 let binder_implementation = m.format_binder_implementation();
-                format!("std::expected<std::optional<{namespace}::{class_name}>, std::string> {namespace}::{class_name}::find_first_by_{member_name}(genORM::database& __db, const {member_parameter}) {{\n    \
-                    static constexpr std::string_view select_statement = \"SELECT * FROM {class_name} WHERE {member_name} = ? LIMIT 1;\";\n    \
+                let comparison = m.format_comparison_operator();
+                format!("std::expected<std::optional<{namespace}::{class_name}>, genORM::error> {namespace}::{class_name}::find_first_by_{member_name}(genORM::database& __db, const {member_parameter}) {{\n    \
+                    static const genORM::cached_statement select_statement{{\"SELECT {select_columns} FROM {class_name} WHERE {member_name} {comparison} ? LIMIT 1;\"}};\n    \
                     if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant {{ return {binder_implementation}; }},\n            \
                     std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
                     if (*select_result) {{\n            \
-                    return {class_name}{{__db, static_cast<uint64_t>(std::get<int64_t>((**select_result)[0])),\n                \
-                    {select_result_to_value}\n            }};\n        \
+                    return from_row(__db, **select_result);\n        \
                     }} else {{\n            \
                     return std::nullopt;\n        \
                     }}\n    \
@@ -188,91 +299,491 @@ fn generate_source(output_dir: &str, file_prefix: &str, namespace: &str, object_
             .collect::<Vec<String>>()
             .join("\n");
 
-        let row_to_value = object_type.members.iter().enumerate()
-            .map(|(index, m)| m.format_select_result_to_value("row", (index + 1) as i32))
-            .collect::<Vec<_>>()
-            .join(",\n                    ");
-
         let find_all_by_implementation = object_type.members.iter()
-            .filter(|m| m.is_index())
+            .filter(|m| m.is_index() && !m.is_unique())
             .map(|m| {
                 let member_name = m.format_name();
                 let member_parameter = m.format_constructor_parameter();
                 let binder_implementation = m.format_binder_implementation();
-                format!("std::expected<std::vector<{namespace}::{class_name}>, std::string> {namespace}::{class_name}::find_all_by_{member_name}(genORM::database& __db, const {member_parameter}) {{\n    \
-                    static constexpr std::string_view select_statement = \"SELECT * FROM {class_name} WHERE {member_name} = ?;\";\n    \
+                let comparison = m.format_comparison_operator();
+                format!("std::expected<std::vector<{namespace}::{class_name}>, genORM::error> {namespace}::{class_name}::find_all_by_{member_name}(genORM::database& __db, const {member_parameter}) {{\n    \
+                    static const genORM::cached_statement select_statement{{\"SELECT {select_columns} FROM {class_name} WHERE {member_name} {comparison} ?;\"}};\n    \
                     if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant {{ return {binder_implementation}; }},\n            \
                     std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
                     std::vector<{class_name}> found_objects;\n        \
                     if (auto& rows = *select_result; not rows.empty()) {{\n            \
                     for (auto& row : rows) {{\n                \
-                    found_objects.emplace_back({class_name}{{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),\n                    \
-                    {row_to_value}\n                }});\n            \
+                    if (auto found_object = from_row(__db, row)) {{\n                    \
+                    found_objects.emplace_back(std::move(*found_object));\n                \
+                    }} else {{\n                    \
+                    return std::unexpected{{std::move(found_object.error())}};\n                \
+                    }}\n            \
                     }}\n        \
                     }}\n        \
                     return found_objects;\n    \
                     }} else {{\n        \
                     return std::unexpected{{std::move(select_result.error())}};\n    \
                     }}\n\
+                    }}\n\
+                    std::expected<genORM::object_range<{namespace}::{class_name}>, genORM::error> {namespace}::{class_name}::iterate_all_by_{member_name}(genORM::database& __db, const {member_parameter}) {{\n    \
+                    static constexpr std::string_view select_statement = \"SELECT {select_columns} FROM {class_name} WHERE {member_name} {comparison} ?;\";\n    \
+                    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant {{ return {binder_implementation}; }},\n            \
+                    std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
+                    return genORM::object_range<{class_name}>{{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) {{ return from_row(__db, row); }}}};\n    \
+                    }} else {{\n        \
+                    return std::unexpected{{std::move(select_result.error())}};\n    \
+                    }}\n\
                     }}")
             })
             .collect::<Vec<String>>()
             .join("\n");
 
-        type_definitions.push_str(&format!("{constructor}{create_implementation}{find_by_rowid_implementation}{find_first_by_implementation}\n{find_all_by_implementation}\n"));
+        let find_by_unique_key_implementation = unique_keys.iter()
+            .map(|key| format_find_one_by_key_implementation(namespace, class_name, &format!("find_by_{}", format_key_name(key)), key, &select_columns, &column_value_variants))
+            .collect::<Vec<String>>()
+            .join("");
+
+        let find_by_composite_index_implementation = composite_indexes.iter()
+            .map(|key| {
+                let key_name = format_key_name(key);
+                format_find_one_by_key_implementation(namespace, class_name, &format!("find_first_by_{key_name}"), key, &select_columns, &column_value_variants)
+                    + &format_find_all_by_key_implementation(namespace, class_name, &format!("find_all_by_{key_name}"), key, &select_columns, &column_value_variants)
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let find_between_implementation = object_type.members.iter()
+            .filter(|m| m.is_index() && m.is_range_index())
+            .map(|m| {
+                let member_name = m.format_name();
+                let member_type = m.format_timestamp_type();
+                let from_value = m.format_range_value_variant("__from");
+                let to_value = m.format_range_value_variant("__to");
+                format!("std::expected<std::vector<{namespace}::{class_name}>, genORM::error> {namespace}::{class_name}::find_all_by_{member_name}_between(genORM::database& __db, const {member_type} __from, const {member_type} __to) {{\n    \
+                    static const genORM::cached_statement select_statement{{\"SELECT {select_columns} FROM {class_name} WHERE {member_name} BETWEEN ? AND ?;\"}};\n    \
+                    if (auto select_result = select_all(__db, select_statement, 2, [=](int value_index) -> genORM::value_variant {{ return value_index == 1 ? {from_value} : {to_value}; }},\n            \
+                    std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
+                    std::vector<{class_name}> found_objects;\n        \
+                    for (auto& row : *select_result) {{\n            \
+                    if (auto found_object = from_row(__db, row)) {{\n                \
+                    found_objects.emplace_back(std::move(*found_object));\n            \
+                    }} else {{\n                \
+                    return std::unexpected{{std::move(found_object.error())}};\n            \
+                    }}\n        \
+                    }}\n        \
+                    return found_objects;\n    \
+                    }} else {{\n        \
+                    return std::unexpected{{std::move(select_result.error())}};\n    \
+                    }}\n\
+                    }}\n")
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let update_statement = object_type.members.iter()
+            .map(|m| format!("{0} = CASE WHEN ? THEN ? ELSE {0} END", m.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // Every member binds whether it changed, followed by its value
+        let field_binder_implementation = (0..object_type.members.len())
+            .map(|i| {
+                let member_impl = object_type.members[i].format_field_binder_implementation();
+                format!("        if (value_index == {}) {{ return static_cast<int64_t>(_changed.test({i})); }}\n        \
+                    if (value_index == {}) {{ return {member_impl}; }}", 2 * i + 1, 2 * i + 2)
+            }).collect::<Vec<_>>().join("\n") + &format!("\n        if (value_index == {}) {{ return static_cast<int64_t>(get_rowid()); }}\n        \
+            throw std::logic_error(\"Implementation error\");\n", 2 * member_count + 1);
+
+        let save_implementation = format!("std::expected<void, genORM::error> {namespace}::{class_name}::save() {{\n    \
+            static const genORM::cached_statement update_statement{{\"UPDATE {class_name} SET {update_statement} WHERE __id = ?;\"}};\n    \
+            const auto binder = [&](int value_index) -> genORM::value_variant {{\n\
+            {field_binder_implementation}    }};\n    \
+            if (auto update_result = update_table(get_database(), update_statement, {}, binder); not update_result) {{\n        \
+            return std::unexpected{{std::move(update_result.error())}};\n    \
+            }} else if (*update_result == 0) {{\n        \
+            return std::unexpected{{genORM::error{{genORM::error_category::not_found, \"Object no longer exists in the database\"}}}};\n    \
+            }}\n    \
+            _changed.reset();\n    \
+            return {{}};\n}}\n", 2 * member_count + 1);
+
+        let delete_by_rowid_implementation = format!("std::expected<uint64_t, genORM::error> {namespace}::{class_name}::delete_by_rowid(genORM::database& __db, const uint64_t __id) {{\n    \
+            static const genORM::cached_statement delete_statement{{\"DELETE FROM {class_name} WHERE __id = ?;\"}};\n    \
+            return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant {{ return static_cast<int64_t>(__id); }});\n\
+            }}\n");
+
+        let delete_all_by_implementation = object_type.members.iter()
+            .filter(|m| m.is_index())
+            .map(|m| {
+                let member_name = m.format_name();
+                let member_parameter = m.format_constructor_parameter();
+                let binder_implementation = m.format_binder_implementation();
+                let comparison = m.format_comparison_operator();
+                format!("std::expected<uint64_t, genORM::error> {namespace}::{class_name}::delete_all_by_{member_name}(genORM::database& __db, const {member_parameter}) {{\n    \
+                    static const genORM::cached_statement delete_statement{{\"DELETE FROM {class_name} WHERE {member_name} {comparison} ?;\"}};\n    \
+                    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant {{ return {binder_implementation}; }});\n\
+                    }}\n")
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let remove_implementation = format!("std::expected<void, genORM::error> {namespace}::{class_name}::remove() const {{\n    \
+            if (auto delete_result = delete_by_rowid(get_database(), get_rowid()); not delete_result) {{\n        \
+            return std::unexpected{{std::move(delete_result.error())}};\n    \
+            }} else if (*delete_result == 0) {{\n        \
+            return std::unexpected{{genORM::error{{genORM::error_category::not_found, \"Object no longer exists in the database\"}}}};\n    \
+            }}\n    \
+            return {{}};\n}}\n");
+
+        let get_object_implementation = object_type.members.iter()
+            .filter_map(|m| m.referenced_type().map(|referenced_type| (m, referenced_type)))
+            .map(|(m, referenced_type)| {
+                let member_name = m.format_name();
+                let find_call = if m.is_allow_null() {
+                    format!("if (not _{member_name}) {{ return std::nullopt; }}\n    \
+                        return {referenced_type}::find_by_rowid(get_database(), *_{member_name});")
+                } else {
+                    format!("return {referenced_type}::find_by_rowid(get_database(), _{member_name});")
+                };
+                format!("std::expected<std::optional<{namespace}::{referenced_type}>, genORM::error> {namespace}::{class_name}::get_{member_name}_object() const {{\n    \
+                    {find_call}\n\
+                    }}\n")
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        let relation_implementation = object_type.relations.iter().flatten()
+            .map(|r| r.format_implementations(namespace, object_type, object_types, enums))
+            .collect::<Result<Vec<String>, String>>()?
+            .join("");
+
+        type_definitions.push_str(&format!("{constructor}{from_row_implementation}{ensure_schema_implementation}{create_implementation}{find_by_rowid_implementation}{find_first_by_implementation}\n{find_all_by_implementation}\n{find_by_unique_key_implementation}{find_by_composite_index_implementation}{find_between_implementation}{delete_by_rowid_implementation}{delete_all_by_implementation}{save_implementation}{remove_implementation}{get_object_implementation}{relation_implementation}"));
     }
 
+    let ensure_schema_calls = object_types.iter()
+        .map(|o| format!("        if (auto ensure_result = {}::ensure_schema(__db); not ensure_result) {{ return ensure_result; }}\n", o.name))
+        .collect::<Vec<String>>()
+        .join("");
+    let initialize_schema_definition = format!("std::expected<void, genORM::error> {namespace}::initialize_schema(genORM::database& __db) {{\n    \
+        return __db.execute_transaction<void>([](genORM::database& __db) -> std::expected<void, genORM::error> {{\n\
+        {ensure_schema_calls}        \
+        return {{}};\n    \
+        }}, genORM::transaction_mode::immediate);\n\
+        }}\n");
+    let migrate_definition = format_migrate_implementation(namespace, enums, object_types, migrations)?;
+
     fs::write(String::from(output_dir) + file_prefix + ".orm.cc",
-              format!("{include_headers}{type_definitions}"))
+              format!("{include_headers}{enum_definitions}{type_definitions}{initialize_schema_definition}{migrate_definition}"))
         .map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
-pub fn generate(output_dir: String, cxx_options: &CxxOptions, object_types: &Vec<ObjectType>) -> Result<(), String> {
-    generate_header(output_dir.as_str(), &cxx_options.file_prefix, &cxx_options.namespace, object_types)?;
-    generate_source(output_dir.as_str(), &cxx_options.file_prefix, &cxx_options.namespace, object_types)?;
+/// Lists the row id and the member columns in the order from_row expects them, optionally qualified by the table name.
+fn format_select_columns(object_type: &ObjectType, qualifier: &str) -> String {
+    std::iter::once("__id")
+        .chain(object_type.members.iter().map(|m| m.name.as_str()))
+        .map(|column| format!("{qualifier}{column}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_create_table_statement(object_type: &ObjectType, enums: &[EnumType], table_name: &str) -> String {
+    let columns = object_type.members.iter()
+        .map(|m| m.format_database_schema(enums))
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("CREATE TABLE IF NOT EXISTS {table_name} (__id INTEGER PRIMARY KEY NOT NULL, {columns}) STRICT;")
+}
+
+/// Returns the statements creating the indexes of single members, composite indexes and unique keys, in that order.
+fn format_create_index_statements(object_type: &ObjectType) -> Result<Vec<String>, String> {
+    let class_name = &object_type.name;
+    let member_indexes = object_type.members.iter()
+        .filter(|m| m.is_index() && !m.is_unique())
+        .map(|m| {
+            let member_name = &m.name;
+            format!("CREATE INDEX IF NOT EXISTS Index_{class_name}_{member_name} ON {class_name}({member_name});")
+        });
+    let composite_indexes = object_type.composite_indexes()?.into_iter()
+        .map(|key| {
+            let index_name = key.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join("_");
+            let columns = key.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", ");
+            format!("CREATE INDEX IF NOT EXISTS Index_{class_name}_{index_name} ON {class_name}({columns});")
+        });
+    let unique_indexes = object_type.unique_keys()?.into_iter()
+        .map(|key| {
+            let index_name = key.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join("_");
+            let columns = key.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", ");
+            format!("CREATE UNIQUE INDEX IF NOT EXISTS UniqueIndex_{class_name}_{index_name} ON {class_name}({columns});")
+        });
+    Ok(member_indexes.chain(composite_indexes).chain(unique_indexes).collect())
+}
+
+fn format_migrate_implementation(namespace: &str, enums: &[EnumType], object_types: &[ObjectType], migrations: &[Migration]) -> Result<String, String> {
+    let table_names = object_types.iter()
+        .map(|o| format!("\"{}\"", o.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let new_database_check = if object_types.is_empty() { String::new() } else {
+        format!("    // A database without any of the tables is new, the tables will be created with the latest schema\n    \
+            if (*user_version == 0) {{\n        \
+            bool is_new = true;\n        \
+            for (const std::string_view table : {{{table_names}}}) {{\n            \
+            if (auto has_table = __db.has_table(table); not has_table) {{ return std::unexpected{{std::move(has_table.error())}}; }} else if (*has_table) {{ is_new = false; }}\n        \
+            }}\n        \
+            if (is_new) {{ return __db.set_user_version(schema_version); }}\n    \
+            }}\n")
+    };
+    let replayed_migrations = replay_migrations(enums, object_types, migrations)?;
+    let migration_blocks = migrations.iter()
+        .zip(&replayed_migrations)
+        .map(|(m, replayed_steps)| m.format_implementation(enums, replayed_steps))
+        .collect::<Result<Vec<String>, String>>()?
+        .join("");
+    Ok(format!("std::expected<void, genORM::error> {namespace}::migrate(genORM::database& __db) {{\n    \
+        const auto user_version = __db.get_user_version();\n    \
+        if (not user_version) {{ return std::unexpected{{std::move(user_version.error())}}; }}\n    \
+        if (schema_version < *user_version) {{ return std::unexpected{{genORM::error{{genORM::error_category::schema_mismatch, \"Database schema version \" + std::to_string(*user_version) + \" is newer than the generated code\"}}}}; }}\n    \
+        if (*user_version == schema_version) {{ return {{}}; }}\n\
+        {new_database_check}    \
+        if (__db.is_in_transaction()) {{ return std::unexpected{{genORM::error{{genORM::error_category::misuse, \"Migrations cannot run inside a transaction\"}}}}; }}\n    \
+        // Rebuilt tables are dropped, which must not cascade into the tables referencing them\n    \
+        const auto foreign_keys = __db.get_foreign_keys();\n    \
+        if (not foreign_keys) {{ return std::unexpected{{std::move(foreign_keys.error())}}; }}\n    \
+        if (auto pragma_result = __db.set_foreign_keys(false); not pragma_result) {{ return std::unexpected{{std::move(pragma_result.error())}}; }}\n    \
+        auto migrate_result = __db.execute_transaction<void>([&](genORM::database& __db) -> std::expected<void, genORM::error> {{\n\
+        {migration_blocks}        \
+        return __db.set_user_version(schema_version);\n    \
+        }}, genORM::transaction_mode::immediate);\n    \
+        if (auto pragma_result = __db.set_foreign_keys(*foreign_keys); not pragma_result) {{ return std::unexpected{{std::move(pragma_result.error())}}; }}\n    \
+        return migrate_result;\n\
+        }}\n"))
+}
+
+/// Joins the names of the members of a multi-column key into a method name suffix, e.g. `owner_and_kind`.
+fn format_key_name(key: &[&Member]) -> String {
+    key.iter().map(|m| m.format_name()).collect::<Vec<_>>().join("_and_")
+}
+
+fn format_key_parameters(key: &[&Member], qualifier: &str) -> String {
+    key.iter().map(|m| format!("{qualifier}{}", m.format_constructor_parameter())).collect::<Vec<_>>().join(", ")
+}
+
+fn format_key_condition(key: &[&Member]) -> String {
+    key.iter().map(|m| format!("{} {} ?", m.name, m.format_comparison_operator())).collect::<Vec<_>>().join(" AND ")
+}
+
+fn format_key_binder_implementation(key: &[&Member]) -> String {
+    key.iter().enumerate()
+        .map(|(i, m)| format!("        if (value_index == {}) {{ return {}; }}\n", i + 1, m.format_binder_implementation()))
+        .collect::<Vec<_>>()
+        .join("") + "        throw std::logic_error(\"Implementation error\");\n"
+}
+
+fn format_find_one_by_key_implementation(namespace: &str, class_name: &str, method_name: &str, key: &[&Member], select_columns: &str, column_value_variants: &str) -> String {
+    let key_parameters = format_key_parameters(key, "const ");
+    let key_condition = format_key_condition(key);
+    let binder_implementation = format_key_binder_implementation(key);
+    let key_size = key.len();
+    format!("std::expected<std::optional<{namespace}::{class_name}>, genORM::error> {namespace}::{class_name}::{method_name}(genORM::database& __db, {key_parameters}) {{\n    \
+        static const genORM::cached_statement select_statement{{\"SELECT {select_columns} FROM {class_name} WHERE {key_condition} LIMIT 1;\"}};\n    \
+        const auto binder = [&](int value_index) -> genORM::value_variant {{\n\
+        {binder_implementation}    }};\n    \
+        if (auto select_result = select_one(__db, select_statement, {key_size}, binder,\n            \
+        std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
+        if (*select_result) {{\n            \
+        return from_row(__db, **select_result);\n        \
+        }} else {{\n            \
+        return std::nullopt;\n        \
+        }}\n    \
+        }} else {{\n        \
+        return std::unexpected{{std::move(select_result.error())}};\n    \
+        }}\n\
+        }}\n")
+}
+
+fn format_find_all_by_key_implementation(namespace: &str, class_name: &str, method_name: &str, key: &[&Member], select_columns: &str, column_value_variants: &str) -> String {
+    let key_parameters = format_key_parameters(key, "const ");
+    let key_condition = format_key_condition(key);
+    let binder_implementation = format_key_binder_implementation(key);
+    let key_size = key.len();
+    format!("std::expected<std::vector<{namespace}::{class_name}>, genORM::error> {namespace}::{class_name}::{method_name}(genORM::database& __db, {key_parameters}) {{\n    \
+        static const genORM::cached_statement select_statement{{\"SELECT {select_columns} FROM {class_name} WHERE {key_condition};\"}};\n    \
+        const auto binder = [&](int value_index) -> genORM::value_variant {{\n\
+        {binder_implementation}    }};\n    \
+        if (auto select_result = select_all(__db, select_statement, {key_size}, binder,\n            \
+        std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
+        std::vector<{class_name}> found_objects;\n        \
+        for (auto& row : *select_result) {{\n            \
+        if (auto found_object = from_row(__db, row)) {{\n                \
+        found_objects.emplace_back(std::move(*found_object));\n            \
+        }} else {{\n                \
+        return std::unexpected{{std::move(found_object.error())}};\n            \
+        }}\n        \
+        }}\n        \
+        return found_objects;\n    \
+        }} else {{\n        \
+        return std::unexpected{{std::move(select_result.error())}};\n    \
+        }}\n\
+        }}\n")
+}
+
+/// Returns every statement creating the tables and indexes described by the config, join tables included.
+pub fn format_schema_statements(enums: &[EnumType], object_types: &[ObjectType]) -> Result<Vec<String>, String> {
+    let mut statements = Vec::new();
+    for enum_type in enums {
+        enum_type.validate(enums)?;
+    }
+    for object_type in object_types {
+        for member in &object_type.members {
+            member.validate(enums, object_types)?;
+        }
+        statements.push(format_create_table_statement(object_type, enums, &object_type.name));
+        statements.extend(format_create_index_statements(object_type)?);
+        for relation in object_type.relations.iter().flatten() {
+            relation.validate(object_type, object_types)?;
+            if relation.is_many_to_many() {
+                let (create_join_table_statement, create_index_statement) = relation.format_create_join_table_statements(&object_type.name);
+                statements.push(create_join_table_statement);
+                statements.push(create_index_statement);
+            }
+        }
+    }
+    // Tables and indexes share a namespace, IF NOT EXISTS would silently skip the second of two equally named objects.
+    // Index names join the column names with underscores, so e.g. an index of member i_io collides with one of [i, io].
+    let mut names = Vec::<&str>::new();
+    for statement in &statements {
+        let name = statement.split_whitespace().skip_while(|word| *word != "EXISTS").nth(1).unwrap_or_default();
+        if names.iter().any(|other| other.eq_ignore_ascii_case(name)) {
+            return Err(format!("Table or index name is generated twice, rename a type, member or relation: {name}"))
+        }
+        names.push(name);
+    }
+    Ok(statements)
+}
+
+pub fn generate(output_dir: String, cxx_options: &CxxOptions, enums: &[EnumType], object_types: &Vec<ObjectType>, migrations: &[Migration]) -> Result<(), String> {
+    format_schema_statements(enums, object_types)?;
+    generate_header(output_dir.as_str(), &cxx_options.file_prefix, &cxx_options.namespace, enums, object_types, migrations)?;
+    generate_source(output_dir.as_str(), &cxx_options.file_prefix, &cxx_options.namespace, enums, object_types, migrations)?;
     Ok(())
 }
 
 trait MemberExt {
     fn is_allow_null(&self) -> bool;
     fn is_index(&self) -> bool;
-    fn validate(&self) -> Result<&dyn MemberExt, String>;
+    fn is_unique(&self) -> bool;
+    fn validate(&self, enums: &[EnumType], object_types: &[ObjectType]) -> Result<&dyn MemberExt, String>;
+    fn find_enum<'a>(&self, enums: &'a [EnumType]) -> Option<&'a EnumType>;
+    fn referenced_type(&self) -> Option<&str>;
+    fn is_range_index(&self) -> bool;
+    fn is_text_storage(&self) -> bool;
+    fn timestamp_precision(&self) -> (&'static str, u32);
+    fn format_timestamp_type(&self) -> String;
+    fn format_range_value_variant(&self, variable_name: &str) -> String;
     fn format_name(&self) -> String;
     fn format_declaration(&self) -> String;
     fn format_constructor_parameter(&self) -> String;
     fn format_constructor_implementation(&self) -> String;
     fn format_constructor_call(&self) -> String;
-    fn format_column_value_variant(&self) -> String;
+    fn format_column_value_variant(&self, enums: &[EnumType]) -> String;
+    fn format_select_result_validation(&self, container_name: &str, index: i32) -> Vec<String>;
     fn format_select_result_to_value(&self, container_name: &str, index: i32) -> String;
     fn format_getter_declaration(&self) -> String;
-    // fn format_setter_declaration(&self) -> String;
-    fn format_database_schema(&self) -> String;
+    fn format_setter_declaration(&self, index: usize) -> String;
+    fn format_database_schema(&self, enums: &[EnumType]) -> String;
+    fn format_database_schema_with_default(&self, enums: &[EnumType], default: Option<&str>) -> String;
     fn format_binder_implementation(&self) -> String;
+    fn format_comparison_operator(&self) -> &'static str;
+    fn format_field_binder_implementation(&self) -> String;
+    fn format_value_variant(&self, variable_name: &str) -> String;
 }
 impl MemberExt for Member {
     fn is_allow_null(&self) -> bool { self.allow_null.unwrap_or(false) }
 
-    fn is_index(&self) -> bool { self.index.unwrap_or(false) }
+    /// Unique members are always backed by an index
+    fn is_index(&self) -> bool { self.index.unwrap_or(false) || self.is_unique() }
 
-    fn validate(&self) -> Result<&dyn MemberExt, String> {
+    fn is_unique(&self) -> bool { self.unique.unwrap_or(false) }
+
+    fn validate(&self, enums: &[EnumType], object_types: &[ObjectType]) -> Result<&dyn MemberExt, String> {
         if self.name.is_empty() {
             return Err("Member name is empty".to_string())
         }
+        if self.type_ != "TIMESTAMP" && (self.storage.is_some() || self.precision.is_some()) {
+            return Err(format!("Only timestamps accept storage and precision: {}", self.name))
+        }
+        if self.referenced_type().is_none() && self.on_delete.is_some() {
+            return Err(format!("Only references accept on-delete: {}", self.name))
+        }
         match self.type_.as_str() {
             "INT32" => { Ok(self) },
             "INT64" => { Ok(self) },
-            "BYTEARRAY" => {
-                if self.is_allow_null() {
-                    return Err("Bytearray cannot be null".to_string())
+            "TEXT" => { Ok(self) },
+            "FLOAT32" => { Ok(self) },
+            "FLOAT64" => { Ok(self) },
+            "BOOL" => { Ok(self) },
+            "TIMESTAMP" => {
+                if let Some(storage) = &self.storage && storage != "INTEGER" && storage != "TEXT" {
+                    return Err(format!("Unexpected timestamp storage: {storage}"))
                 }
-                if self.is_index() {
-                    return Err("Bytearray cannot be an index".to_string())
+                if let Some(precision) = &self.precision && !matches!(precision.as_str(), "SECONDS" | "MILLISECONDS" | "MICROSECONDS" | "NANOSECONDS") {
+                    return Err(format!("Unexpected timestamp precision: {precision}"))
                 }
                 Ok(self)
             },
-            _ => Err(format!("Unexpected type: {}", self.type_))
+            "BYTEARRAY" => { Ok(self) },
+            _ if self.referenced_type().is_some() => {
+                let referenced_type = self.referenced_type().unwrap_or_default();
+                if !object_types.iter().any(|o| o.name == referenced_type) {
+                    return Err(format!("Referenced object type does not exist: {referenced_type}"))
+                }
+                match self.on_delete.as_deref() {
+                    None | Some("CASCADE") | Some("RESTRICT") | Some("NO ACTION") => Ok(self),
+                    Some("SET NULL") | Some("SET DEFAULT") if self.is_allow_null() => Ok(self),
+                    Some("SET NULL") | Some("SET DEFAULT") => Err(format!("Reference must allow null to use on-delete SET NULL or SET DEFAULT: {}", self.name)),
+                    Some(on_delete) => Err(format!("Unexpected on-delete action: {on_delete}"))
+                }
+            },
+            _ => {
+                if self.find_enum(enums).is_none() {
+                    return Err(format!("Unexpected type: {}", self.type_))
+                }
+                Ok(self)
+            }
+        }
+    }
+
+    fn find_enum<'a>(&self, enums: &'a [EnumType]) -> Option<&'a EnumType> {
+        enums.iter().find(|e| e.name == self.type_)
+    }
+
+    fn referenced_type(&self) -> Option<&str> {
+        self.type_.strip_prefix("REF(").and_then(|s| s.strip_suffix(")"))
+    }
+
+    fn is_range_index(&self) -> bool { self.type_ == "TIMESTAMP" }
+
+    fn is_text_storage(&self) -> bool { self.storage.as_deref() == Some("TEXT") }
+
+    fn timestamp_precision(&self) -> (&'static str, u32) {
+        match self.precision.as_deref() {
+            Some("SECONDS") => ("std::chrono::seconds", 0),
+            Some("MILLISECONDS") => ("std::chrono::milliseconds", 3),
+            Some("NANOSECONDS") => ("std::chrono::nanoseconds", 9),
+            _ => ("std::chrono::microseconds", 6)
+        }
+    }
+
+    fn format_timestamp_type(&self) -> String {
+        format!("std::chrono::sys_time<{}>", self.timestamp_precision().0)
+    }
+
+    fn format_range_value_variant(&self, variable_name: &str) -> String {
+        if self.is_text_storage() {
+            format!("genORM::value_variant{{genORM::format_timestamp({variable_name}, {})}}", self.timestamp_precision().1)
+        } else {
+            format!("genORM::value_variant{{static_cast<int64_t>({variable_name}.time_since_epoch().count())}}")
         }
     }
 
@@ -281,8 +792,8 @@ impl MemberExt for Member {
     fn format_declaration(&self) -> String {
         let name = &self.name;
         let description = self.description.as_ref()
-            .map(|s| String::from("        /// ") + &s + "\n")
-            .unwrap_or(String::new());
+            .map(|s| String::from("        /// ") + s + "\n")
+            .unwrap_or_default();
         match self.type_.as_str() {
             "INT32" => {
                 let type_ = if self.is_allow_null() { "std::optional<int32_t>" } else { "int32_t" };
@@ -294,11 +805,46 @@ impl MemberExt for Member {
                 let declaration = format!("        {type_} _{name};");
                 format!("{description}{declaration}\n")
             },
+            "FLOAT32" => {
+                let type_ = if self.is_allow_null() { "std::optional<float>" } else { "float" };
+                let declaration = format!("        {type_} _{name};");
+                format!("{description}{declaration}\n")
+            },
+            "FLOAT64" => {
+                let type_ = if self.is_allow_null() { "std::optional<double>" } else { "double" };
+                let declaration = format!("        {type_} _{name};");
+                format!("{description}{declaration}\n")
+            },
+            "BOOL" => {
+                let type_ = if self.is_allow_null() { "std::optional<bool>" } else { "bool" };
+                let declaration = format!("        {type_} _{name};");
+                format!("{description}{declaration}\n")
+            },
+            "TIMESTAMP" => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.format_timestamp_type()) } else { self.format_timestamp_type() };
+                let declaration = format!("        {type_} _{name};");
+                format!("{description}{declaration}\n")
+            },
+            "TEXT" => {
+                let type_ = if self.is_allow_null() { "std::optional<std::string>" } else { "std::string" };
+                let declaration = format!("        {type_} _{name};");
+                format!("{description}{declaration}\n")
+            },
             "BYTEARRAY" => {
-                let declaration = format!("        std::vector<uint8_t> _{name};");
+                let type_ = if self.is_allow_null() { "std::optional<std::vector<uint8_t>>" } else { "std::vector<uint8_t>" };
+                let declaration = format!("        {type_} _{name};");
+                format!("{description}{declaration}\n")
+            },
+            _ if self.referenced_type().is_some() => {
+                let type_ = if self.is_allow_null() { "std::optional<uint64_t>" } else { "uint64_t" };
+                let declaration = format!("        {type_} _{name};");
                 format!("{description}{declaration}\n")
             },
-            _ => String::new()
+            _ => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.type_) } else { self.type_.to_string() };
+                let declaration = format!("        {type_} _{name};");
+                format!("{description}{declaration}\n")
+            }
         }
     }
 
@@ -313,17 +859,45 @@ impl MemberExt for Member {
                 let type_ = if self.is_allow_null() { "std::optional<int64_t>" } else { "int64_t" };
                 format!("{type_} {name}")
             },
+            "FLOAT32" => {
+                let type_ = if self.is_allow_null() { "std::optional<float>" } else { "float" };
+                format!("{type_} {name}")
+            },
+            "FLOAT64" => {
+                let type_ = if self.is_allow_null() { "std::optional<double>" } else { "double" };
+                format!("{type_} {name}")
+            },
+            "BOOL" => {
+                let type_ = if self.is_allow_null() { "std::optional<bool>" } else { "bool" };
+                format!("{type_} {name}")
+            },
+            "TIMESTAMP" => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.format_timestamp_type()) } else { self.format_timestamp_type() };
+                format!("{type_} {name}")
+            },
+            "TEXT" => {
+                let type_ = if self.is_allow_null() { "std::optional<std::string>" } else { "std::string" };
+                format!("{type_} {name}")
+            },
             "BYTEARRAY" => {
-                format!("std::vector<uint8_t> {name}")
+                let type_ = if self.is_allow_null() { "std::optional<std::vector<uint8_t>>" } else { "std::vector<uint8_t>" };
+                format!("{type_} {name}")
             },
-            _ => String::new()
+            _ if self.referenced_type().is_some() => {
+                let type_ = if self.is_allow_null() { "std::optional<uint64_t>" } else { "uint64_t" };
+                format!("{type_} {name}")
+            },
+            _ => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.type_) } else { self.type_.to_string() };
+                format!("{type_} {name}")
+            }
         }
     }
 
     fn format_constructor_implementation(&self) -> String {
         let name = &self.name;
         match self.type_.as_str() {
-            "BYTEARRAY" => {
+            "TEXT" | "BYTEARRAY" => {
                 format!("_{name}(std::move({name}))")
             },
             _ => format!("_{name}({name})")
@@ -333,19 +907,65 @@ impl MemberExt for Member {
     fn format_constructor_call(&self) -> String {
         let name = &self.name;
         match self.type_.as_str() {
-            "BYTEARRAY" => {
+            "TEXT" | "BYTEARRAY" => {
                 format!("std::move({name})")
             },
-            _ => format!("{name}")
+            _ => name.to_string()
         }
     }
 
-    fn format_column_value_variant(&self) -> String {
+    fn format_column_value_variant(&self, enums: &[EnumType]) -> String {
         match self.type_.as_str() {
             "INT32" => { String::from("int32_t{}") },
             "INT64" => { String::from("int64_t{}") },
+            "FLOAT32" | "FLOAT64" => { String::from("double{}") },
+            "BOOL" => { String::from("int32_t{}") },
+            "TIMESTAMP" => { if self.is_text_storage() { String::from("std::string{}") } else { String::from("int64_t{}") } },
+            "TEXT" => { String::from("std::string{}") },
             "BYTEARRAY" => { String::from("std::vector<uint8_t>{}") },
-            _ => String::new()
+            _ if self.referenced_type().is_some() => { String::from("int64_t{}") },
+            _ => match self.find_enum(enums) {
+                Some(e) if e.is_text_storage() => String::from("std::string{}"),
+                Some(_) => String::from("int32_t{}"),
+                None => String::new()
+            }
+        }
+    }
+
+    fn format_select_result_validation(&self, container_name: &str, index: i32) -> Vec<String> {
+        let name = &self.name;
+        match self.type_.as_str() {
+            "INT32" | "INT64" | "FLOAT32" | "FLOAT64" | "BOOL" | "TEXT" | "BYTEARRAY" => vec![],
+            "TIMESTAMP" if !self.is_text_storage() => vec![],
+            _ if self.referenced_type().is_some() => vec![],
+            "TIMESTAMP" => {
+                // Parsed at the precision of the member, which limits the range of representable timestamps
+                let (duration, _) = self.timestamp_precision();
+                if self.is_allow_null() {
+                    vec![
+                        format!("std::optional<{}> __{name};", self.format_timestamp_type()),
+                        format!("if (const auto* text = std::get_if<std::string>(&{container_name}[{index}])) {{ if (auto value = genORM::parse_timestamp<{duration}>(*text)) {{ __{name} = *value; }} else {{ return std::unexpected{{std::move(value.error())}}; }} }}"),
+                    ]
+                } else {
+                    vec![
+                        format!("const auto __{name} = genORM::parse_timestamp<{duration}>(std::get<std::string>({container_name}[{index}]));"),
+                        format!("if (not __{name}) {{ return std::unexpected{{__{name}.error()}}; }}"),
+                    ]
+                }
+            },
+            enum_name => {
+                if self.is_allow_null() {
+                    vec![
+                        format!("std::optional<{enum_name}> __{name};"),
+                        format!("if (not std::holds_alternative<std::monostate>({container_name}[{index}])) {{ if (auto value = {enum_name}_from_value({container_name}[{index}])) {{ __{name} = *value; }} else {{ return std::unexpected{{std::move(value.error())}}; }} }}"),
+                    ]
+                } else {
+                    vec![
+                        format!("const auto __{name} = {enum_name}_from_value({container_name}[{index}]);"),
+                        format!("if (not __{name}) {{ return std::unexpected{{__{name}.error()}}; }}"),
+                    ]
+                }
+            }
         }
     }
 
@@ -365,10 +985,58 @@ impl MemberExt for Member {
                     format!("std::get<int64_t>({container_name}[{index}])")
                 }
             },
+            "FLOAT32" => {
+                if self.is_allow_null() {
+                    format!("std::holds_alternative<double>({container_name}[{index}]) ? static_cast<float>(std::get<double>({container_name}[{index}])) : std::optional<float>{{}}")
+                } else {
+                    format!("static_cast<float>(std::get<double>({container_name}[{index}]))")
+                }
+            },
+            "FLOAT64" => {
+                if self.is_allow_null() {
+                    format!("std::holds_alternative<double>({container_name}[{index}]) ? std::get<double>({container_name}[{index}]) : std::optional<double>{{}}")
+                } else {
+                    format!("std::get<double>({container_name}[{index}])")
+                }
+            },
+            "BOOL" => {
+                if self.is_allow_null() {
+                    format!("std::holds_alternative<int32_t>({container_name}[{index}]) ? std::get<int32_t>({container_name}[{index}]) != 0 : std::optional<bool>{{}}")
+                } else {
+                    format!("std::get<int32_t>({container_name}[{index}]) != 0")
+                }
+            },
+            "TIMESTAMP" => {
+                let (duration, _) = self.timestamp_precision();
+                let type_ = self.format_timestamp_type();
+                match (self.is_text_storage(), self.is_allow_null()) {
+                    (true, true) => format!("__{}", self.name),
+                    (true, false) => format!("*__{}", self.name),
+                    (false, true) => format!("std::holds_alternative<int64_t>({container_name}[{index}]) ? {type_}{{{duration}{{std::get<int64_t>({container_name}[{index}])}}}} : std::optional<{type_}>{{}}"),
+                    (false, false) => format!("{type_}{{{duration}{{std::get<int64_t>({container_name}[{index}])}}}}"),
+                }
+            },
+            "TEXT" => {
+                if self.is_allow_null() {
+                    format!("std::holds_alternative<std::string>({container_name}[{index}]) ? std::move(std::get<std::string>({container_name}[{index}])) : std::optional<std::string>{{}}")
+                } else {
+                    format!("std::move(std::get<std::string>({container_name}[{index}]))")
+                }
+            },
             "BYTEARRAY" => {
-                format!("std::holds_alternative<std::vector<uint8_t>>({container_name}[{index}]) ? std::move(std::get<std::vector<uint8_t>>({container_name}[{index}])) : std::vector<uint8_t>{{}}")
+                let type_ = if self.is_allow_null() { "std::optional<std::vector<uint8_t>>" } else { "std::vector<uint8_t>" };
+                format!("std::holds_alternative<std::vector<uint8_t>>({container_name}[{index}]) ? std::move(std::get<std::vector<uint8_t>>({container_name}[{index}])) : {type_}{{}}")
             },
-            _ => String::new()
+            _ if self.referenced_type().is_some() => {
+                if self.is_allow_null() {
+                    format!("std::holds_alternative<int64_t>({container_name}[{index}]) ? static_cast<uint64_t>(std::get<int64_t>({container_name}[{index}])) : std::optional<uint64_t>{{}}")
+                } else {
+                    format!("static_cast<uint64_t>(std::get<int64_t>({container_name}[{index}]))")
+                }
+            },
+            _ => {
+                if self.is_allow_null() { format!("__{}", self.name) } else { format!("*__{}", self.name) }
+            }
         }
     }
 
@@ -383,52 +1051,787 @@ impl MemberExt for Member {
                 let type_ = if self.is_allow_null() { "std::optional<int64_t>" } else { "int64_t" };
                 format!("        [[nodiscard]] {type_} get_{name}() const {{ return _{name}; }}\n")
             },
+            "FLOAT32" => {
+                let type_ = if self.is_allow_null() { "std::optional<float>" } else { "float" };
+                format!("        [[nodiscard]] {type_} get_{name}() const {{ return _{name}; }}\n")
+            },
+            "FLOAT64" => {
+                let type_ = if self.is_allow_null() { "std::optional<double>" } else { "double" };
+                format!("        [[nodiscard]] {type_} get_{name}() const {{ return _{name}; }}\n")
+            },
+            "BOOL" => {
+                let type_ = if self.is_allow_null() { "std::optional<bool>" } else { "bool" };
+                format!("        [[nodiscard]] {type_} get_{name}() const {{ return _{name}; }}\n")
+            },
+            "TIMESTAMP" => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.format_timestamp_type()) } else { self.format_timestamp_type() };
+                format!("        [[nodiscard]] {type_} get_{name}() const {{ return _{name}; }}\n")
+            },
+            "TEXT" => {
+                let type_ = if self.is_allow_null() { "std::optional<std::string>" } else { "std::string" };
+                format!("        [[nodiscard]] const {type_}& get_{name}() const {{ return _{name}; }}\n")
+            },
             "BYTEARRAY" => {
-                format!("        [[nodiscard]] const std::vector<uint8_t>& get_{name}() const {{ return _{name}; }}\n")
-            },
-            _ => String::new()
-        }
-    }
-
-    // fn format_setter_declaration(&self) -> String {
-    //     let name = &self.name;
-    //     match self.type_.as_str() {
-    //         "INT32" => {
-    //             let type_ = if self.is_allow_null() { "std::optional<int32_t>" } else { "int32_t" };
-    //             format!("        void set_{name}({type_});\n")
-    //         },
-    //         "INT64" => {
-    //             let type_ = if self.is_allow_null() { "std::optional<int64_t>" } else { "int64_t" };
-    //             format!("        void set_{name}({type_});\n")
-    //         },
-    //         "BYTEARRAY" => {
-    //             format!("        void set_{name}(std::vector<uint8_t>);\n")
-    //         },
-    //         _ => String::new()
-    //     }
-    // }
-
-    fn format_database_schema(&self) -> String {
+                let type_ = if self.is_allow_null() { "std::optional<std::vector<uint8_t>>" } else { "std::vector<uint8_t>" };
+                format!("        [[nodiscard]] const {type_}& get_{name}() const {{ return _{name}; }}\n")
+            },
+            _ if self.referenced_type().is_some() => {
+                let type_ = if self.is_allow_null() { "std::optional<uint64_t>" } else { "uint64_t" };
+                let referenced_type = self.referenced_type().unwrap_or_default();
+                format!("        [[nodiscard]] {type_} get_{name}() const {{ return _{name}; }}\n        \
+                    std::expected<std::optional<{referenced_type}>, genORM::error> get_{name}_object() const;\n")
+            },
+            _ => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.type_) } else { self.type_.to_string() };
+                format!("        [[nodiscard]] {type_} get_{name}() const {{ return _{name}; }}\n")
+            }
+        }
+    }
+
+    fn format_setter_declaration(&self, index: usize) -> String {
+        let name = &self.name;
+        match self.type_.as_str() {
+            "INT32" => {
+                let type_ = if self.is_allow_null() { "std::optional<int32_t>" } else { "int32_t" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            },
+            "INT64" => {
+                let type_ = if self.is_allow_null() { "std::optional<int64_t>" } else { "int64_t" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            },
+            "FLOAT32" => {
+                let type_ = if self.is_allow_null() { "std::optional<float>" } else { "float" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            },
+            "FLOAT64" => {
+                let type_ = if self.is_allow_null() { "std::optional<double>" } else { "double" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            },
+            "BOOL" => {
+                let type_ = if self.is_allow_null() { "std::optional<bool>" } else { "bool" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            },
+            "TIMESTAMP" => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.format_timestamp_type()) } else { self.format_timestamp_type() };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            },
+            "TEXT" => {
+                let type_ = if self.is_allow_null() { "std::optional<std::string>" } else { "std::string" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = std::move({name}); _changed.set({index}); }}\n")
+            },
+            "BYTEARRAY" => {
+                let type_ = if self.is_allow_null() { "std::optional<std::vector<uint8_t>>" } else { "std::vector<uint8_t>" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = std::move({name}); _changed.set({index}); }}\n")
+            },
+            _ if self.referenced_type().is_some() => {
+                let type_ = if self.is_allow_null() { "std::optional<uint64_t>" } else { "uint64_t" };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            },
+            _ => {
+                let type_ = if self.is_allow_null() { format!("std::optional<{}>", self.type_) } else { self.type_.to_string() };
+                format!("        void set_{name}({type_} {name}) {{ _{name} = {name}; _changed.set({index}); }}\n")
+            }
+        }
+    }
+
+    fn format_database_schema(&self, enums: &[EnumType]) -> String {
+        self.format_database_schema_with_default(enums, None)
+    }
+
+    /// Formats the column definition, with the given SQL literal in place of the default value of the type
+    fn format_database_schema_with_default(&self, enums: &[EnumType], default: Option<&str>) -> String {
         let name = &self.name;
         match self.type_.as_str() {
             "INT32" | "INT64" => {
                 let nullability = if self.is_allow_null() { "" } else { "NOT NULL" };
-                let default_value = if self.is_allow_null() { "NULL" } else { "0" };
+                let default_value = default.unwrap_or(if self.is_allow_null() { "NULL" } else { "0" });
                 format!("{name} INTEGER {nullability} DEFAULT {default_value}")
             },
+            "FLOAT32" | "FLOAT64" => {
+                let nullability = if self.is_allow_null() { "" } else { "NOT NULL" };
+                let default_value = default.unwrap_or(if self.is_allow_null() { "NULL" } else { "0.0" });
+                format!("{name} REAL {nullability} DEFAULT {default_value}")
+            },
+            "BOOL" => {
+                let nullability = if self.is_allow_null() { "" } else { "NOT NULL" };
+                let default_value = default.unwrap_or(if self.is_allow_null() { "NULL" } else { "0" });
+                format!("{name} INTEGER {nullability} DEFAULT {default_value} CHECK({name} IN (0, 1))")
+            },
+            "TIMESTAMP" => {
+                let nullability = if self.is_allow_null() { "" } else { "NOT NULL" };
+                if self.is_text_storage() {
+                    let fraction = match self.timestamp_precision().1 {
+                        0 => String::new(),
+                        digits => format!(".{}", "0".repeat(digits as usize))
+                    };
+                    let default_value = default.map(String::from).unwrap_or(if self.is_allow_null() { String::from("NULL") } else { format!("'1970-01-01T00:00:00{fraction}Z'") });
+                    format!("{name} TEXT {nullability} DEFAULT {default_value}")
+                } else {
+                    let default_value = default.unwrap_or(if self.is_allow_null() { "NULL" } else { "0" });
+                    format!("{name} INTEGER {nullability} DEFAULT {default_value}")
+                }
+            },
+            "TEXT" => {
+                let nullability = if self.is_allow_null() { "" } else { "NOT NULL" };
+                let default_value = default.unwrap_or(if self.is_allow_null() { "NULL" } else { "''" });
+                format!("{name} TEXT {nullability} DEFAULT {default_value}")
+            },
             "BYTEARRAY" => {
-                format!("{name} BLOB")
+                // Before nullable byte arrays existed every BYTEARRAY column was a plain nullable BLOB, databases created
+                // back then need a rebuild migration to match this definition
+                let nullability = if self.is_allow_null() { "" } else { "NOT NULL" };
+                let default_value = default.unwrap_or(if self.is_allow_null() { "NULL" } else { "x''" });
+                format!("{name} BLOB {nullability} DEFAULT {default_value}")
             },
-            _ => String::new()
+            _ if self.referenced_type().is_some() => {
+                // A non-null reference has no sensible default, the target must always be given
+                let nullability = if self.is_allow_null() { "DEFAULT NULL" } else { "NOT NULL" };
+                let referenced_type = self.referenced_type().unwrap_or_default();
+                let on_delete = self.on_delete.as_ref().map(|a| format!(" ON DELETE {a}")).unwrap_or_default();
+                format!("{name} INTEGER {nullability} REFERENCES {referenced_type}(__id){on_delete}")
+            },
+            _ => match self.find_enum(enums) {
+                Some(e) => {
+                    let (column_type, first_value) = if e.is_text_storage() { ("TEXT", format!("'{}'", e.values[0])) } else { ("INTEGER", String::from("0")) };
+                    let nullability = if self.is_allow_null() { "" } else { "NOT NULL" };
+                    let default_value = default.map(String::from).unwrap_or(if self.is_allow_null() { String::from("NULL") } else { first_value });
+                    format!("{name} {column_type} {nullability} DEFAULT {default_value}")
+                },
+                None => String::new()
+            }
         }
     }
 
     fn format_binder_implementation(&self) -> String {
-        let name = &self.name;
+        self.format_value_variant(&self.name)
+    }
+
+    fn format_comparison_operator(&self) -> &'static str {
+        // `= NULL` never matches, `IS NULL` does. Both behave the same for non-null values.
+        if self.is_allow_null() { "IS" } else { "=" }
+    }
+
+    fn format_field_binder_implementation(&self) -> String {
+        self.format_value_variant(&format!("_{}", self.name))
+    }
+
+    fn format_value_variant(&self, variable_name: &str) -> String {
+        let value = match self.type_.as_str() {
+            "FLOAT32" => if self.is_allow_null() { format!("static_cast<double>(*{variable_name})") } else { format!("static_cast<double>({variable_name})") },
+            "BOOL" => if self.is_allow_null() { format!("static_cast<int32_t>(*{variable_name})") } else { format!("static_cast<int32_t>({variable_name})") },
+            "TIMESTAMP" => {
+                let time = if self.is_allow_null() { format!("(*{variable_name})") } else { variable_name.to_string() };
+                if self.is_text_storage() {
+                    format!("genORM::format_timestamp({time}, {})", self.timestamp_precision().1)
+                } else {
+                    format!("static_cast<int64_t>({time}.time_since_epoch().count())")
+                }
+            },
+            _ if self.referenced_type().is_some() => if self.is_allow_null() { format!("static_cast<int64_t>(*{variable_name})") } else { format!("static_cast<int64_t>({variable_name})") },
+            "INT32" | "INT64" | "FLOAT64" | "TEXT" | "BYTEARRAY" => if self.is_allow_null() { format!("*{variable_name}") } else { variable_name.to_string() },
+            enum_name => if self.is_allow_null() { format!("{enum_name}_to_value(*{variable_name})") } else { format!("{enum_name}_to_value({variable_name})") }
+        };
         if self.is_allow_null() {
-            format!("{name} ? genORM::value_variant{{*{name}}} : std::monostate{{}}")
+            format!("{variable_name} ? genORM::value_variant{{{value}}} : std::monostate{{}}")
         } else {
-            format!("{name}")
+            value
+        }
+    }
+}
+
+trait ObjectTypeExt {
+    fn find_member(&self, name: &str) -> Result<&Member, String>;
+    fn find_columns(&self, columns: &[String]) -> Result<Vec<&Member>, String>;
+    fn unique_keys(&self) -> Result<Vec<Vec<&Member>>, String>;
+    fn composite_indexes(&self) -> Result<Vec<Vec<&Member>>, String>;
+}
+impl ObjectTypeExt for ObjectType {
+    fn find_member(&self, name: &str) -> Result<&Member, String> {
+        self.members.iter()
+            .find(|m| m.name == name)
+            .ok_or(format!("Object type {} has no member named {name}", self.name))
+    }
+
+    fn find_columns(&self, columns: &[String]) -> Result<Vec<&Member>, String> {
+        if columns.is_empty() {
+            return Err(format!("Column list of {} is empty", self.name))
+        }
+        let members = columns.iter()
+            .map(|c| self.find_member(c))
+            .collect::<Result<Vec<_>, String>>()?;
+        if members.iter().enumerate().any(|(i, m)| members[..i].iter().any(|other| other.name == m.name)) {
+            return Err(format!("Column list of {} has duplicate columns", self.name))
+        }
+        Ok(members)
+    }
+
+    /// Returns the column sets that must be unique, both from unique members and from unique constraints.
+    fn unique_keys(&self) -> Result<Vec<Vec<&Member>>, String> {
+        let mut keys = self.members.iter()
+            .filter(|m| m.is_unique())
+            .map(|m| vec![m])
+            .collect::<Vec<_>>();
+        for constraint in self.unique_constraints.iter().flatten() {
+            keys.push(self.find_columns(&constraint.columns)?);
+        }
+        Ok(keys)
+    }
+
+    fn composite_indexes(&self) -> Result<Vec<Vec<&Member>>, String> {
+        self.indexes.iter().flatten()
+            .map(|index| {
+                if index.columns.len() < 2 {
+                    return Err(format!("Index of {} needs at least two columns, use the member's index flag instead", self.name))
+                }
+                self.find_columns(&index.columns)
+            })
+            .collect()
+    }
+}
+
+trait EnumTypeExt {
+    fn is_text_storage(&self) -> bool;
+    fn validate(&self, enums: &[EnumType]) -> Result<&dyn EnumTypeExt, String>;
+    fn format_declaration(&self) -> String;
+    fn format_conversion_implementations(&self, namespace: &str) -> String;
+}
+impl EnumTypeExt for EnumType {
+    fn is_text_storage(&self) -> bool { self.storage.as_deref() == Some("TEXT") }
+
+    fn validate(&self, enums: &[EnumType]) -> Result<&dyn EnumTypeExt, String> {
+        let name = &self.name;
+        if name.is_empty() {
+            return Err("Enum name is empty".to_string())
+        }
+        if let "INT32" | "INT64" | "FLOAT32" | "FLOAT64" | "BOOL" | "TEXT" | "TIMESTAMP" | "BYTEARRAY" = name.as_str() {
+            return Err(format!("Enum name clashes with a builtin type: {name}"))
+        }
+        if enums.iter().filter(|e| &e.name == name).count() != 1 {
+            return Err(format!("Enum is declared more than once: {name}"))
+        }
+        if self.values.is_empty() {
+            return Err(format!("Enum has no values: {name}"))
         }
+        if self.values.iter().enumerate().any(|(i, v)| self.values[..i].contains(v)) {
+            return Err(format!("Enum has duplicate values: {name}"))
+        }
+        // Values become enumerators, and are written as is into string literals and SQL defaults of TEXT storage
+        if let Some(value) = self.values.iter().find(|v| !is_identifier(v) || is_cxx_keyword(v)) {
+            return Err(format!("Enum value is not a valid C++ identifier: {name}::{value}"))
+        }
+        match self.storage.as_deref() {
+            None | Some("INTEGER") | Some("TEXT") => Ok(self),
+            Some(storage) => Err(format!("Unexpected enum storage: {storage}"))
+        }
+    }
+
+    fn format_declaration(&self) -> String {
+        let name = &self.name;
+        let description = self.description.as_ref()
+            .map(|s| String::from("    /// ") + s + "\n")
+            .unwrap_or_default();
+        let values = self.values.iter()
+            .map(|v| format!("        {v},\n"))
+            .collect::<Vec<_>>()
+            .join("");
+        format!("{description}    enum class {name} {{\n{values}    }};\n    \
+            genORM::value_variant {name}_to_value({name});\n    \
+            std::expected<{name}, genORM::error> {name}_from_value(const genORM::value_variant&);\n\n")
+    }
+
+    fn format_conversion_implementations(&self, namespace: &str) -> String {
+        let name = &self.name;
+        let to_value_cases = self.values.iter().enumerate()
+            .map(|(i, v)| {
+                let value = if self.is_text_storage() { format!("std::string{{\"{v}\"}}") } else { format!("int32_t{{{i}}}") };
+                format!("        case {name}::{v}: return {value};\n")
+            })
+            .collect::<Vec<_>>()
+            .join("");
+        let from_value_implementation = if self.is_text_storage() {
+            let branches = self.values.iter()
+                .map(|v| format!("        if (*text == \"{v}\") {{ return {name}::{v}; }}\n"))
+                .collect::<Vec<_>>()
+                .join("");
+            format!("    if (const auto* text = std::get_if<std::string>(&value)) {{\n\
+                {branches}        \
+                return std::unexpected{{genORM::error{{genORM::error_category::invalid_value, \"Unknown {name} value: \" + *text}}}};\n    \
+                }}\n")
+        } else {
+            let cases = self.values.iter().enumerate()
+                .map(|(i, v)| format!("            case {i}: return {name}::{v};\n"))
+                .collect::<Vec<_>>()
+                .join("");
+            format!("    if (const auto* integer = std::get_if<int32_t>(&value)) {{\n        \
+                switch (*integer) {{\n\
+                {cases}            \
+                default: return std::unexpected{{genORM::error{{genORM::error_category::invalid_value, \"Unknown {name} value: \" + std::to_string(*integer)}}}};\n        \
+                }}\n    \
+                }}\n")
+        };
+        format!("genORM::value_variant {namespace}::{name}_to_value(const {name} value) {{\n    \
+            switch (value) {{\n\
+            {to_value_cases}    \
+            }}\n    \
+            throw std::logic_error(\"Implementation error\");\n\
+            }}\n\
+            std::expected<{namespace}::{name}, genORM::error> {namespace}::{name}_from_value(const genORM::value_variant& value) {{\n\
+            {from_value_implementation}    \
+            return std::unexpected{{genORM::error{{genORM::error_category::invalid_value, \"Unexpected {name} value type\"}}}};\n\
+            }}\n")
+    }
+}
+
+trait RelationExt {
+    fn is_many_to_many(&self) -> bool;
+    fn validate<'a>(&self, owner: &ObjectType, object_types: &'a [ObjectType]) -> Result<&'a ObjectType, String>;
+    fn find_via<'a>(&self, owner: &ObjectType, target: &'a ObjectType) -> Result<&'a Member, String>;
+    fn format_join_table_name(&self, owner_name: &str) -> String;
+    fn format_declarations(&self) -> String;
+    fn format_create_join_table_statements(&self, owner_name: &str) -> (String, String);
+    fn format_create_join_table_block(&self, owner_name: &str) -> String;
+    fn format_implementations(&self, namespace: &str, owner: &ObjectType, object_types: &[ObjectType], enums: &[EnumType]) -> Result<String, String>;
+}
+impl RelationExt for Relation {
+    fn is_many_to_many(&self) -> bool { self.type_ == "many-to-many" }
+
+    /// Returns the target object type of the relation
+    fn validate<'a>(&self, owner: &ObjectType, object_types: &'a [ObjectType]) -> Result<&'a ObjectType, String> {
+        let name = &self.name;
+        if name.is_empty() {
+            return Err(format!("Relation name of {} is empty", owner.name))
+        }
+        if owner.members.iter().any(|m| &m.name == name) || owner.relations.iter().flatten().filter(|r| &r.name == name).count() != 1 {
+            return Err(format!("Relation name of {} clashes with another member or relation: {name}", owner.name))
+        }
+        let target = object_types.iter()
+            .find(|o| o.name == self.target)
+            .ok_or(format!("Related object type does not exist: {}", self.target))?;
+        match self.type_.as_str() {
+            "has-many" => { self.find_via(owner, target)?; },
+            "many-to-many" => {
+                if self.via.is_some() {
+                    return Err(format!("Many-to-many relations are stored in a join table and do not accept via: {name}"))
+                }
+            },
+            type_ => { return Err(format!("Unexpected relation type: {type_}")) }
+        }
+        Ok(target)
+    }
+
+    /// Returns the reference member of the target that points back to the owner
+    fn find_via<'a>(&self, owner: &ObjectType, target: &'a ObjectType) -> Result<&'a Member, String> {
+        let via = match &self.via {
+            Some(via) => target.find_member(via)?,
+            None => {
+                let mut references = target.members.iter().filter(|m| m.referenced_type() == Some(owner.name.as_str()));
+                match (references.next(), references.next()) {
+                    (Some(via), None) => via,
+                    _ => return Err(format!("Relation {} needs via to pick exactly one reference of {} to {}", self.name, target.name, owner.name))
+                }
+            }
+        };
+        if via.referenced_type() != Some(owner.name.as_str()) {
+            return Err(format!("Member {} of {} does not reference {}", via.name, target.name, owner.name))
+        }
+        if !via.is_index() {
+            return Err(format!("Member {} of {} must be indexed to back relation {}", via.name, target.name, self.name))
+        }
+        Ok(via)
+    }
+
+    fn format_join_table_name(&self, owner_name: &str) -> String {
+        format!("{owner_name}_{}", self.name)
+    }
+
+    fn format_declarations(&self) -> String {
+        let name = &self.name;
+        let target = &self.target;
+        let description = self.description.as_ref()
+            .map(|s| String::from("        /// ") + s + "\n")
+            .unwrap_or_default();
+        let get_declaration = format!("        std::expected<std::vector<{target}>, genORM::error> get_{name}() const;\n");
+        if self.is_many_to_many() {
+            format!("{description}{get_declaration}        \
+                std::expected<void, genORM::error> link_{name}(const {target}& other) const;\n        \
+                std::expected<void, genORM::error> unlink_{name}(const {target}& other) const;\n")
+        } else {
+            format!("{description}{get_declaration}")
+        }
+    }
+
+    /// Returns the statements creating the join table and its index
+    fn format_create_join_table_statements(&self, owner_name: &str) -> (String, String) {
+        let join_table = self.format_join_table_name(owner_name);
+        let target = &self.target;
+        (format!("CREATE TABLE IF NOT EXISTS {join_table} (__owner_id INTEGER NOT NULL REFERENCES {owner_name}(__id) ON DELETE CASCADE, __target_id INTEGER NOT NULL REFERENCES {target}(__id) ON DELETE CASCADE, PRIMARY KEY (__owner_id, __target_id)) STRICT;"),
+         format!("CREATE INDEX IF NOT EXISTS Index_{join_table}___target_id ON {join_table}(__target_id);"))
+    }
+
+    fn format_create_join_table_block(&self, owner_name: &str) -> String {
+        let (create_join_table_statement, create_index_statement) = self.format_create_join_table_statements(owner_name);
+        format!("    {{\n        \
+            static constexpr std::string_view create_join_table_statement = \"{create_join_table_statement}\";\n        \
+            if (auto create_table_result = create_table_if_not_exists(__db, create_join_table_statement); not create_table_result) {{ return std::unexpected{{std::move(create_table_result.error())}}; }}\n        \
+            static constexpr std::string_view create_index_statement = \"{create_index_statement}\";\n        \
+            if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) {{ return std::unexpected{{std::move(create_index_result.error())}}; }}\n    \
+            }}\n")
+    }
+
+    fn format_implementations(&self, namespace: &str, owner: &ObjectType, object_types: &[ObjectType], enums: &[EnumType]) -> Result<String, String> {
+        let name = &self.name;
+        let class_name = &owner.name;
+        let target = self.validate(owner, object_types)?;
+        let target_name = &target.name;
+        if !self.is_many_to_many() {
+            let via = self.find_via(owner, target)?;
+            let via_name = via.format_name();
+            // Unique members only have a finder for a single object
+            if via.is_unique() {
+                return Ok(format!("std::expected<std::vector<{namespace}::{target_name}>, genORM::error> {namespace}::{class_name}::get_{name}() const {{\n    \
+                    std::vector<{target_name}> found_objects;\n    \
+                    if (auto found_object = {target_name}::find_by_{via_name}(get_database(), get_rowid()); not found_object) {{\n        \
+                    return std::unexpected{{std::move(found_object.error())}};\n    \
+                    }} else if (*found_object) {{\n        \
+                    found_objects.emplace_back(std::move(**found_object));\n    \
+                    }}\n    \
+                    return found_objects;\n\
+                    }}\n"))
+            }
+            return Ok(format!("std::expected<std::vector<{namespace}::{target_name}>, genORM::error> {namespace}::{class_name}::get_{name}() const {{\n    \
+                return {target_name}::find_all_by_{via_name}(get_database(), get_rowid());\n\
+                }}\n"))
+        }
+
+        let join_table = self.format_join_table_name(class_name);
+        let column_value_variants = target.members.iter()
+            .map(|m| m.format_column_value_variant(enums))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let select_columns = format_select_columns(target, &format!("{target_name}."));
+        let owner_binder = "if (value_index == 1) { return static_cast<int64_t>(get_rowid()); }";
+        let target_binder = "if (value_index == 2) { return static_cast<int64_t>(other.get_rowid()); }";
+        Ok(format!("std::expected<std::vector<{namespace}::{target_name}>, genORM::error> {namespace}::{class_name}::get_{name}() const {{\n    \
+            static const genORM::cached_statement select_statement{{\"SELECT {select_columns} FROM {target_name} JOIN {join_table} ON {target_name}.__id = {join_table}.__target_id WHERE {join_table}.__owner_id = ?;\"}};\n    \
+            if (auto select_result = select_all(get_database(), select_statement, 1, [this](int) -> genORM::value_variant {{ return static_cast<int64_t>(get_rowid()); }},\n            \
+            std::vector<genORM::value_variant>{{{{int64_t{{}}, {column_value_variants}}}}})) {{\n        \
+            std::vector<{target_name}> found_objects;\n        \
+            for (auto& row : *select_result) {{\n            \
+            if (auto found_object = {target_name}::from_row(get_database(), row)) {{\n                \
+            found_objects.emplace_back(std::move(*found_object));\n            \
+            }} else {{\n                \
+            return std::unexpected{{std::move(found_object.error())}};\n            \
+            }}\n        \
+            }}\n        \
+            return found_objects;\n    \
+            }} else {{\n        \
+            return std::unexpected{{std::move(select_result.error())}};\n    \
+            }}\n\
+            }}\n\
+            std::expected<void, genORM::error> {namespace}::{class_name}::link_{name}(const {target_name}& other) const {{\n    \
+            // Linking twice is not an error, the pair is only stored once\n    \
+            static const genORM::cached_statement insert_statement{{\"INSERT OR IGNORE INTO {join_table} VALUES (?, ?);\"}};\n    \
+            const auto binder = [&](int value_index) -> genORM::value_variant {{\n        \
+            {owner_binder}\n        \
+            {target_binder}\n        \
+            throw std::logic_error(\"Implementation error\");\n    \
+            }};\n    \
+            if (auto insert_result = insert_into_table(get_database(), insert_statement, 2, binder); not insert_result) {{\n        \
+            return std::unexpected{{std::move(insert_result.error())}};\n    \
+            }}\n    \
+            return {{}};\n\
+            }}\n\
+            std::expected<void, genORM::error> {namespace}::{class_name}::unlink_{name}(const {target_name}& other) const {{\n    \
+            static const genORM::cached_statement delete_statement{{\"DELETE FROM {join_table} WHERE __owner_id = ? AND __target_id = ?;\"}};\n    \
+            const auto binder = [&](int value_index) -> genORM::value_variant {{\n        \
+            {owner_binder}\n        \
+            {target_binder}\n        \
+            throw std::logic_error(\"Implementation error\");\n    \
+            }};\n    \
+            if (auto delete_result = delete_from_table(get_database(), delete_statement, 2, binder); not delete_result) {{\n        \
+            return std::unexpected{{std::move(delete_result.error())}};\n    \
+            }}\n    \
+            return {{}};\n\
+            }}\n"))
+    }
+}
+
+/// Replays the migrations backwards from the current object types. Returns the object types as they were after each
+/// step of each migration, so that the steps can be validated and generated against the schema of their own version.
+fn replay_migrations(enums: &[EnumType], object_types: &[ObjectType], migrations: &[Migration]) -> Result<Vec<Vec<Vec<ObjectType>>>, String> {
+    for (index, migration) in migrations.iter().enumerate() {
+        migration.validate(index)?;
+    }
+    let mut replayed_object_types = object_types.to_vec();
+    let mut replayed_migrations = Vec::new();
+    for migration in migrations.iter().rev() {
+        let mut replayed_steps = Vec::new();
+        for step in migration.steps.iter().rev() {
+            step.validate(enums, &replayed_object_types)
+                .map_err(|e| format!("Migration {}: {e}", migration.version))?;
+            replayed_steps.push(replayed_object_types.clone());
+            step.revert(&mut replayed_object_types);
+        }
+        replayed_steps.reverse();
+        replayed_migrations.push(replayed_steps);
+    }
+    replayed_migrations.reverse();
+    Ok(replayed_migrations)
+}
+
+trait MigrationExt {
+    fn validate(&self, index: usize) -> Result<&dyn MigrationExt, String>;
+    fn format_implementation(&self, enums: &[EnumType], replayed_steps: &[Vec<ObjectType>]) -> Result<String, String>;
+}
+impl MigrationExt for Migration {
+    fn validate(&self, index: usize) -> Result<&dyn MigrationExt, String> {
+        // Versions are recorded in the database, they cannot be reordered or skipped
+        if self.version as usize != index + 1 {
+            return Err(format!("Migration versions must count up from 1, found {} at position {}", self.version, index + 1))
+        }
+        if self.steps.is_empty() {
+            return Err(format!("Migration {} has no steps", self.version))
+        }
+        Ok(self)
+    }
+
+    /// `replayed_steps` holds the object types as they were after each step
+    fn format_implementation(&self, enums: &[EnumType], replayed_steps: &[Vec<ObjectType>]) -> Result<String, String> {
+        let version = self.version;
+        let description = self.description.as_ref()
+            .map(|s| String::from("            // ") + s + "\n")
+            .unwrap_or_default();
+        let steps = self.steps.iter()
+            .zip(replayed_steps)
+            .map(|(s, object_types)| s.format_implementation(enums, object_types))
+            .collect::<Result<Vec<String>, String>>()?
+            .join("");
+        Ok(format!("        if (*user_version < {version}) {{\n{description}{steps}        }}\n"))
+    }
+}
+
+trait MigrationStepExt {
+    fn added_member(&self) -> Option<Member>;
+    fn validate<'a>(&self, enums: &[EnumType], object_types: &'a [ObjectType]) -> Result<Option<&'a ObjectType>, String>;
+    fn revert(&self, object_types: &mut [ObjectType]);
+    fn format_implementation(&self, enums: &[EnumType], object_types: &[ObjectType]) -> Result<String, String>;
+}
+impl MigrationStepExt for MigrationStep {
+    /// Returns the member as it was added by an add-member step
+    fn added_member(&self) -> Option<Member> {
+        let column = self.column.as_ref()?;
+        Some(Member {
+            name: self.member.clone()?,
+            description: None,
+            type_: column.type_.clone(),
+            allow_null: column.allow_null,
+            index: None,
+            unique: None,
+            storage: column.storage.clone(),
+            precision: column.precision.clone(),
+            on_delete: column.on_delete.clone(),
+        })
+    }
+
+    /// Validates the step against the object types as they were right after it. Returns the object type the step
+    /// migrates, if any.
+    fn validate<'a>(&self, enums: &[EnumType], object_types: &'a [ObjectType]) -> Result<Option<&'a ObjectType>, String> {
+        let type_ = &self.type_;
+        if self.from.is_some() && type_ != "rename-member" {
+            return Err(format!("Only rename-member steps accept from: {type_}"))
+        }
+        if self.column.is_some() && type_ != "add-member" {
+            return Err(format!("Only add-member steps accept column: {type_}"))
+        }
+        if self.statement.is_some() != (type_ == "sql") {
+            return Err(format!("Exactly the sql steps need a statement: {type_}"))
+        }
+        if type_ == "sql" {
+            if self.object_type.is_some() || self.member.is_some() {
+                return Err("Sql steps do not accept object-type or member".to_string())
+            }
+            return Ok(None)
+        }
+        let object_type_name = self.object_type.as_ref()
+            .ok_or(format!("Migration step {type_} needs an object-type"))?;
+        let object_type = object_types.iter()
+            .find(|o| &o.name == object_type_name)
+            .ok_or(format!("Migrated object type does not exist: {object_type_name}"))?;
+        match type_.as_str() {
+            "add-member" => {
+                let member_name = self.member.as_ref()
+                    .ok_or(format!("Migration step {type_} needs a member"))?;
+                object_type.find_member(member_name)?;
+                // The current definition of the member may have changed since, the step records its own
+                let member = self.added_member()
+                    .ok_or(format!("Migration step add-member needs a column definition: {member_name}"))?;
+                member.validate(enums, object_types)?;
+                if member.referenced_type().is_some() && !member.is_allow_null() {
+                    return Err(format!("A non-null reference has no default and cannot be added to existing rows: {member_name}"))
+                }
+                if member.referenced_type().is_some() && self.column.as_ref().is_some_and(|c| c.default.is_some()) {
+                    return Err(format!("References do not accept a default: {member_name}"))
+                }
+            },
+            "rename-member" => {
+                let member_name = self.member.as_ref()
+                    .ok_or(format!("Migration step {type_} needs a member"))?;
+                object_type.find_member(member_name)?;
+                let from = self.from.as_ref()
+                    .ok_or(format!("Migration step rename-member needs from: {member_name}"))?;
+                if object_type.find_member(from).is_ok() {
+                    return Err(format!("Renamed member still exists after the rename: {from}"))
+                }
+            },
+            "rebuild" => {
+                if self.member.is_some() {
+                    return Err("Rebuild steps do not accept member".to_string())
+                }
+            },
+            _ => { return Err(format!("Unexpected migration step type: {type_}")) }
+        }
+        Ok(Some(object_type))
+    }
+
+    /// Turns the object types as they were after the step into the object types as they were before it
+    fn revert(&self, object_types: &mut [ObjectType]) {
+        let (Some(object_type_name), Some(member_name)) = (&self.object_type, &self.member) else { return };
+        let Some(object_type) = object_types.iter_mut().find(|o| &o.name == object_type_name) else { return };
+        match self.type_.as_str() {
+            "add-member" => {
+                object_type.members.retain(|m| &m.name != member_name);
+                if let Some(unique_constraints) = &mut object_type.unique_constraints {
+                    unique_constraints.retain(|c| !c.columns.contains(member_name));
+                }
+                if let Some(indexes) = &mut object_type.indexes {
+                    indexes.retain(|i| !i.columns.contains(member_name));
+                }
+            },
+            "rename-member" => {
+                let from = self.from.clone().unwrap_or_default();
+                let columns = object_type.unique_constraints.iter_mut().flatten().map(|c| &mut c.columns)
+                    .chain(object_type.indexes.iter_mut().flatten().map(|i| &mut i.columns));
+                for column in columns.flatten().chain(object_type.members.iter_mut().map(|m| &mut m.name)) {
+                    if column == member_name {
+                        *column = from.clone();
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Generates the step against the object types as they were right after it
+    fn format_implementation(&self, enums: &[EnumType], object_types: &[ObjectType]) -> Result<String, String> {
+        let Some(object_type) = self.validate(enums, object_types)? else {
+            let statement = self.statement.as_deref().unwrap_or_default().replace('\\', "\\\\").replace('"', "\\\"");
+            return Ok(format!("            if (auto sql_result = __db.execute(\"{statement}\"); not sql_result) {{ return std::unexpected{{std::move(sql_result.error())}}; }}\n"))
+        };
+        let class_name = &object_type.name;
+        let member_name = self.member.as_deref().unwrap_or_default();
+        match self.type_.as_str() {
+            "add-member" => {
+                let default = self.column.as_ref().and_then(|c| c.default.as_deref());
+                let member_schema = self.added_member()
+                    .ok_or(format!("Migration step add-member needs a column definition: {member_name}"))?
+                    .format_database_schema_with_default(enums, default);
+                Ok(format!("            if (auto has_table = __db.has_table(\"{class_name}\"); not has_table) {{\n                \
+                    return std::unexpected{{std::move(has_table.error())}};\n            \
+                    }} else if (auto has_column = __db.has_column(\"{class_name}\", \"{member_name}\"); not has_column) {{\n                \
+                    return std::unexpected{{std::move(has_column.error())}};\n            \
+                    }} else if (*has_table and not *has_column) {{\n                \
+                    if (auto alter_result = __db.execute(\"ALTER TABLE {class_name} ADD COLUMN {member_schema};\"); not alter_result) {{ return std::unexpected{{std::move(alter_result.error())}}; }}\n            \
+                    }}\n"))
+            },
+            "rename-member" => {
+                let from = self.from.as_deref().unwrap_or_default();
+                Ok(format!("            if (auto has_column = __db.has_column(\"{class_name}\", \"{from}\"); not has_column) {{\n                \
+                    return std::unexpected{{std::move(has_column.error())}};\n            \
+                    }} else if (*has_column) {{\n                \
+                    if (auto alter_result = __db.execute(\"ALTER TABLE {class_name} RENAME COLUMN {from} TO {member_name};\"); not alter_result) {{ return std::unexpected{{std::move(alter_result.error())}}; }}\n            \
+                    }}\n"))
+            },
+            _ => {
+                let create_table_statement = format_create_table_statement(object_type, enums, &format!("{class_name}__new"));
+                let columns = object_type.members.iter()
+                    .map(|m| format!("\"{}\"", m.name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let create_index_lines = format_create_index_statements(object_type)?.iter()
+                    .map(|statement| format!("                if (auto index_result = __db.execute(\"{statement}\"); not index_result) {{ return std::unexpected{{std::move(index_result.error())}}; }}\n"))
+                    .collect::<Vec<_>>()
+                    .join("");
+                Ok(format!("            if (auto has_table = __db.has_table(\"{class_name}\"); not has_table) {{\n                \
+                    return std::unexpected{{std::move(has_table.error())}};\n            \
+                    }} else if (*has_table) {{\n                \
+                    if (auto create_result = __db.execute(\"{create_table_statement}\"); not create_result) {{ return std::unexpected{{std::move(create_result.error())}}; }}\n                \
+                    // Members missing from the old table are filled with their defaults\n                \
+                    std::string columns = \"__id\";\n                \
+                    for (const std::string_view column : {{{columns}}}) {{\n                    \
+                    if (auto has_column = __db.has_column(\"{class_name}\", column); not has_column) {{ return std::unexpected{{std::move(has_column.error())}}; }} else if (*has_column) {{ columns += \", \" + std::string{{column}}; }}\n                \
+                    }}\n                \
+                    if (auto copy_result = __db.execute(\"INSERT INTO {class_name}__new (\" + columns + \") SELECT \" + columns + \" FROM {class_name};\"); not copy_result) {{ return std::unexpected{{std::move(copy_result.error())}}; }}\n                \
+                    if (auto drop_result = __db.execute(\"DROP TABLE {class_name};\"); not drop_result) {{ return std::unexpected{{std::move(drop_result.error())}}; }}\n                \
+                    if (auto rename_result = __db.execute(\"ALTER TABLE {class_name}__new RENAME TO {class_name};\"); not rename_result) {{ return std::unexpected{{std::move(rename_result.error())}}; }}\n\
+                    {create_index_lines}                \
+                    if (auto check_result = __db.check_foreign_keys(\"{class_name}\"); not check_result) {{ return std::unexpected{{std::move(check_result.error())}}; }}\n            \
+                    }}\n"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Config;
+    use super::format_schema_statements;
+
+    fn schema_statements(object_types: &str) -> Result<Vec<String>, String> {
+        schema_statements_with_enums("[]", object_types)
+    }
+
+    fn schema_statements_with_enums(enums: &str, object_types: &str) -> Result<Vec<String>, String> {
+        let config: Config = serde_json::from_str(&format!("{{ \"genORM-config-version\": 1, \"enums\": {enums}, \"object-types\": {object_types} }}")).unwrap();
+        format_schema_statements(config.enums.as_deref().unwrap_or_default(), &config.object_types)
+    }
+
+    #[test]
+    fn index_name_collisions() {
+        let member_and_composite = schema_statements(r#"[{ "name": "T", "members": [
+            { "name": "i", "type": "INT32" }, { "name": "io", "type": "INT32" }, { "name": "i_io", "type": "INT32", "index": true }
+        ], "indexes": [{ "columns": ["i", "io"] }] }]"#);
+        assert_eq!(member_and_composite, Err(String::from("Table or index name is generated twice, rename a type, member or relation: Index_T_i_io")));
+
+        let composites = schema_statements(r#"[{ "name": "T", "members": [
+            { "name": "a", "type": "INT32" }, { "name": "b_c", "type": "INT32" }, { "name": "a_b", "type": "INT32" }, { "name": "c", "type": "INT32" }
+        ], "indexes": [{ "columns": ["a", "b_c"] }, { "columns": ["a_b", "c"] }] }]"#);
+        assert_eq!(composites, Err(String::from("Table or index name is generated twice, rename a type, member or relation: Index_T_a_b_c")));
+
+        let types = schema_statements(r#"[
+            { "name": "A", "members": [{ "name": "b_c", "type": "INT32", "index": true }] },
+            { "name": "A_b", "members": [{ "name": "c", "type": "INT32", "index": true }] }
+        ]"#);
+        assert!(types.is_err());
+
+        let distinct = schema_statements(r#"[{ "name": "T", "members": [
+            { "name": "i", "type": "INT32", "index": true }, { "name": "io", "type": "INT32", "index": true }
+        ], "indexes": [{ "columns": ["i", "io"] }] }]"#);
+        assert!(distinct.is_ok());
+    }
+    #[test]
+    fn enum_values() {
+        let object_types = r#"[{ "name": "T", "members": [{ "name": "s", "type": "Shape" }] }]"#;
+        let quoted = schema_statements_with_enums(r#"[{ "name": "Shape", "storage": "TEXT", "values": ["SQUARE", "it's"] }]"#, object_types);
+        assert_eq!(quoted, Err(String::from("Enum value is not a valid C++ identifier: Shape::it's")));
+
+        let keyword = schema_statements_with_enums(r#"[{ "name": "Shape", "values": ["SQUARE", "delete"] }]"#, object_types);
+        assert_eq!(keyword, Err(String::from("Enum value is not a valid C++ identifier: Shape::delete")));
+
+        // Enums are validated even when no member uses them
+        let unused = schema_statements_with_enums(r#"[{ "name": "Shape", "values": ["SQUARE"] }, { "name": "Color", "values": [] }]"#, object_types);
+        assert_eq!(unused, Err(String::from("Enum has no values: Color")));
+
+        let valid = schema_statements_with_enums(r#"[{ "name": "Shape", "storage": "TEXT", "values": ["SQUARE", "_round2"] }]"#, object_types);
+        assert!(valid.is_ok());
     }
 }
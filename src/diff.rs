@@ -0,0 +1,217 @@
+use rusqlite::{Connection, OpenFlags};
+
+struct ColumnInfo {
+    name: String,
+    type_: String,
+    not_null: bool,
+}
+
+struct IndexInfo {
+    columns: Vec<String>,
+    unique: bool,
+}
+
+struct TableInfo {
+    name: String,
+    columns: Vec<ColumnInfo>,
+    indexes: Vec<IndexInfo>,
+}
+
+/// Compares the schema of the database file against the schema created by the given statements. Returns one line per
+/// difference, an empty list means the database matches the config.
+pub fn diff(schema_statements: &[String], database_path: &str) -> Result<Vec<String>, String> {
+    let actual_connection = Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| e.to_string())?;
+    diff_connection(schema_statements, &actual_connection)
+}
+
+pub fn diff_connection(schema_statements: &[String], actual_connection: &Connection) -> Result<Vec<String>, String> {
+    // The expected schema is materialized in memory so that both sides are introspected the same way
+    let expected_connection = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    for statement in schema_statements {
+        expected_connection.execute_batch(statement).map_err(|e| format!("{e}: {statement}"))?;
+    }
+    let expected_tables = introspect(&expected_connection)?;
+    let actual_tables = introspect(actual_connection)?;
+
+    let mut differences = Vec::new();
+    for table in &expected_tables {
+        let table_name = &table.name;
+        let Some(actual_table) = actual_tables.iter().find(|t| &t.name == table_name) else {
+            differences.push(format!("Missing table: {table_name}"));
+            continue
+        };
+        for column in &table.columns {
+            let column_name = &column.name;
+            match actual_table.columns.iter().find(|c| &c.name == column_name) {
+                None => differences.push(format!("Missing column: {table_name}.{column_name}")),
+                Some(actual_column) => {
+                    if !actual_column.type_.eq_ignore_ascii_case(&column.type_) {
+                        differences.push(format!("Type mismatch on {table_name}.{column_name}: expected {}, found {}", column.type_, actual_column.type_));
+                    }
+                    if actual_column.not_null != column.not_null {
+                        differences.push(format!("Nullability mismatch on {table_name}.{column_name}: expected {}, found {}",
+                                                 format_nullability(column.not_null), format_nullability(actual_column.not_null)));
+                    }
+                }
+            }
+        }
+        for actual_column in &actual_table.columns {
+            if !table.columns.iter().any(|c| c.name == actual_column.name) {
+                differences.push(format!("Extra column: {table_name}.{}", actual_column.name));
+            }
+        }
+        // Indexes are matched by their columns, the name does not matter for lookups
+        for index in &table.indexes {
+            if !actual_table.indexes.iter().any(|i| i.columns == index.columns && i.unique == index.unique) {
+                let kind = if index.unique { "unique index" } else { "index" };
+                differences.push(format!("Missing {kind}: {table_name}({})", index.columns.join(", ")));
+            }
+        }
+    }
+    for actual_table in &actual_tables {
+        if !expected_tables.iter().any(|t| t.name == actual_table.name) {
+            differences.push(format!("Extra table: {}", actual_table.name));
+        }
+    }
+    Ok(differences)
+}
+
+fn format_nullability(not_null: bool) -> &'static str {
+    if not_null { "NOT NULL" } else { "nullable" }
+}
+
+fn introspect(connection: &Connection) -> Result<Vec<TableInfo>, String> {
+    let mut table_statement = connection
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name;")
+        .map_err(|e| e.to_string())?;
+    let table_names = table_statement.query_map([], |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())?;
+
+    table_names.into_iter()
+        .map(|name| {
+            let columns = introspect_columns(connection, &name)?;
+            let indexes = introspect_indexes(connection, &name)?;
+            Ok(TableInfo { name, columns, indexes })
+        })
+        .collect()
+}
+
+fn introspect_columns(connection: &Connection, table_name: &str) -> Result<Vec<ColumnInfo>, String> {
+    let mut statement = connection
+        .prepare("SELECT name, type, \"notnull\" FROM pragma_table_info(?1) ORDER BY cid;")
+        .map_err(|e| e.to_string())?;
+    statement.query_map([table_name], |row| Ok(ColumnInfo { name: row.get(0)?, type_: row.get(1)?, not_null: row.get(2)? }))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())
+}
+
+fn introspect_indexes(connection: &Connection, table_name: &str) -> Result<Vec<IndexInfo>, String> {
+    let mut list_statement = connection
+        .prepare("SELECT name, \"unique\" FROM pragma_index_list(?1);")
+        .map_err(|e| e.to_string())?;
+    let index_list = list_statement.query_map([table_name], |row| Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?)))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())?;
+
+    let mut info_statement = connection
+        .prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno;")
+        .map_err(|e| e.to_string())?;
+    index_list.into_iter()
+        .map(|(index_name, unique)| {
+            // Expression indexes have no column names
+            let columns = info_statement.query_map([&index_name], |row| row.get::<_, Option<String>>(0).map(|c| c.unwrap_or(String::from("<expression>"))))
+                .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                .map_err(|e| e.to_string())?;
+            Ok(IndexInfo { columns, unique })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+    use crate::Config;
+    use crate::cxx::format_schema_statements;
+    use super::diff_connection;
+
+    const CONFIG: &str = r#"{
+        "genORM-config-version": 1,
+        "object-types": [
+            {
+                "name": "Owner",
+                "members": [
+                    { "name": "n", "type": "INT32", "index": true },
+                    { "name": "s", "type": "TEXT", "allow-null": true }
+                ]
+            },
+            {
+                "name": "Pet",
+                "members": [
+                    { "name": "owner", "type": "REF(Owner)", "on-delete": "CASCADE" },
+                    { "name": "tag", "type": "TEXT", "unique": true }
+                ]
+            }
+        ]
+    }"#;
+
+    fn schema_statements() -> Vec<String> {
+        let config: Config = serde_json::from_str(CONFIG).unwrap();
+        format_schema_statements(&[], &config.object_types).unwrap()
+    }
+
+    /// Creates an in-memory database with the config's schema, after applying the replacements to the statements
+    fn database(replacements: &[(&str, &str)]) -> Connection {
+        let connection = Connection::open_in_memory().unwrap();
+        for statement in schema_statements() {
+            let statement = replacements.iter().fold(statement, |statement, (from, to)| statement.replace(from, to));
+            connection.execute_batch(&statement).unwrap();
+        }
+        connection
+    }
+
+    #[test]
+    fn matching_database() {
+        assert_eq!(diff_connection(&schema_statements(), &database(&[])).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn missing_table() {
+        let connection = database(&[]);
+        connection.execute_batch("DROP TABLE Pet;").unwrap();
+        assert_eq!(diff_connection(&schema_statements(), &connection).unwrap(), vec!["Missing table: Pet"]);
+    }
+
+    #[test]
+    fn missing_column() {
+        let connection = database(&[(", s TEXT  DEFAULT NULL", "")]);
+        assert_eq!(diff_connection(&schema_statements(), &connection).unwrap(), vec!["Missing column: Owner.s"]);
+    }
+
+    #[test]
+    fn extra_table_and_column() {
+        let connection = database(&[]);
+        connection.execute_batch("CREATE TABLE Legacy (__id INTEGER PRIMARY KEY NOT NULL); ALTER TABLE Owner ADD COLUMN legacy TEXT;").unwrap();
+        assert_eq!(diff_connection(&schema_statements(), &connection).unwrap(), vec!["Extra column: Owner.legacy", "Extra table: Legacy"]);
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let connection = database(&[("s TEXT  DEFAULT NULL", "s BLOB DEFAULT NULL")]);
+        assert_eq!(diff_connection(&schema_statements(), &connection).unwrap(), vec!["Type mismatch on Owner.s: expected TEXT, found BLOB"]);
+    }
+
+    #[test]
+    fn nullability_mismatch() {
+        let connection = database(&[("n INTEGER NOT NULL", "n INTEGER")]);
+        assert_eq!(diff_connection(&schema_statements(), &connection).unwrap(), vec!["Nullability mismatch on Owner.n: expected NOT NULL, found nullable"]);
+    }
+
+    #[test]
+    fn missing_index() {
+        let connection = database(&[]);
+        connection.execute_batch("DROP INDEX Index_Owner_n; DROP INDEX UniqueIndex_Pet_tag; CREATE INDEX Other_Pet_tag ON Pet(tag);").unwrap();
+        assert_eq!(diff_connection(&schema_statements(), &connection).unwrap(), vec!["Missing index: Owner(n)", "Missing unique index: Pet(tag)"]);
+    }
+}
@@ -0,0 +1,513 @@
+use rusqlite::{Connection, OpenFlags};
+use crate::{Config, Index, Member, ObjectType, Relation, UniqueConstraint};
+use crate::cxx::format_schema_statements;
+use crate::util::{is_cxx_keyword, is_identifier};
+
+struct ColumnInfo {
+    name: String,
+    type_: String,
+    not_null: bool,
+    primary_key: bool,
+}
+
+struct ForeignKeyInfo {
+    target: String,
+    from: String,
+    to: Option<String>,
+    on_delete: String,
+    column_count: usize,
+}
+
+/// A foreign key of an imported column. It is resolved once it is known which tables were imported.
+struct ReferenceInfo {
+    table: String,
+    column: String,
+    target: String,
+    on_delete: Option<String>,
+}
+
+struct IndexInfo {
+    name: String,
+    columns: Vec<Option<String>>,
+    unique: bool,
+    origin: String,
+    partial: bool,
+}
+
+/// Builds a config describing the tables of the database. Constructs that cannot be represented are skipped and
+/// reported as warnings.
+pub fn import(database_path: &str) -> Result<(Config, Vec<String>), String> {
+    let connection = Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| e.to_string())?;
+    import_connection(&connection)
+}
+
+pub fn import_connection(connection: &Connection) -> Result<(Config, Vec<String>), String> {
+    let mut warnings = Vec::new();
+
+    let mut schema_statement = connection
+        .prepare("SELECT type, name, tbl_name, sql FROM sqlite_master WHERE name NOT LIKE 'sqlite_%' ORDER BY rowid;")
+        .map_err(|e| e.to_string())?;
+    let schema = schema_statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, Option<String>>(3)?)))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())?;
+    let tables = schema.iter()
+        .filter(|(type_, ..)| type_ == "table")
+        .map(|(_, name, _, sql)| (name.as_str(), sql.as_deref().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    for (type_, name, ..) in &schema {
+        if type_ == "view" || type_ == "trigger" {
+            warnings.push(format!("Skipping {type_} {name}, genORM cannot represent it"));
+        }
+    }
+
+    let mut object_types = Vec::new();
+    let mut join_tables = Vec::new();
+    let mut references = Vec::new();
+    for (table_name, sql) in &tables {
+        let columns = query_columns(connection, table_name)?;
+        let foreign_keys = query_foreign_keys(connection, table_name)?;
+        if is_join_table(&columns) {
+            join_tables.push((table_name.to_string(), foreign_keys));
+            continue
+        }
+        if let Some(object_type) = import_table(connection, table_name, sql, columns, foreign_keys, &mut references, &mut warnings)? {
+            object_types.push(object_type);
+        }
+    }
+
+    // Tables that were skipped cannot be referenced, their columns keep the type of the column
+    let imported_names = object_types.iter().map(|o| o.name.clone()).collect::<Vec<_>>();
+    for ReferenceInfo { table, column, target, on_delete } in references {
+        let Some(member) = object_types.iter_mut()
+            .find(|o| o.name == table)
+            .and_then(|o| o.members.iter_mut().find(|m| m.name == column)) else { continue };
+        if !imported_names.contains(&target) {
+            warnings.push(format!("Column {table}.{column} references {target}, which was not imported, importing it as {}", member.type_));
+            continue
+        }
+        member.type_ = format!("REF({target})");
+        match on_delete.as_deref() {
+            Some(action @ ("SET NULL" | "SET DEFAULT")) if member.allow_null != Some(true) => {
+                warnings.push(format!("Dropping ON DELETE {action} of {table}.{column}, genORM only allows it on nullable references"));
+            },
+            _ => member.on_delete = on_delete,
+        }
+    }
+
+    // Join tables created for many-to-many relations are named after the owner and the relation
+    for (join_table, foreign_keys) in join_tables {
+        let owner = foreign_keys.iter().find(|fk| fk.from == "__owner_id").map(|fk| fk.target.clone());
+        let target = foreign_keys.iter().find(|fk| fk.from == "__target_id").map(|fk| fk.target.clone());
+        let relation_name = owner.as_ref().and_then(|owner| join_table.strip_prefix(&format!("{owner}_")));
+        let target = target.filter(|target| imported_names.contains(target));
+        match (owner.as_ref().and_then(|owner| object_types.iter_mut().find(|o| &o.name == owner)), target, relation_name) {
+            (Some(owner_type), Some(target), Some(relation_name)) => {
+                owner_type.relations.get_or_insert_with(Vec::new).push(Relation {
+                    name: relation_name.to_string(),
+                    description: None,
+                    type_: String::from("many-to-many"),
+                    target,
+                    via: None,
+                });
+            },
+            _ => warnings.push(format!("Skipping table {join_table}, it looks like a join table but does not follow genORM's naming or joins skipped tables"))
+        }
+    }
+
+    if object_types.is_empty() {
+        warnings.push(String::from("The database contains no tables genORM can represent"));
+    }
+    // The generator must accept whatever is written out
+    format_schema_statements(&[], &object_types).map_err(|e| format!("Imported config does not pass validation: {e}"))?;
+    warnings.push(String::from("Add cxx-options to the config to generate code"));
+    Ok((Config { config_version: 1, cxx_options: None, enums: None, object_types, migrations: None }, warnings))
+}
+
+fn import_table(connection: &Connection, table_name: &str, sql: &str, columns: Vec<ColumnInfo>, foreign_keys: Vec<ForeignKeyInfo>,
+                references: &mut Vec<ReferenceInfo>, warnings: &mut Vec<String>) -> Result<Option<ObjectType>, String> {
+    if sql.to_uppercase().contains("WITHOUT ROWID") {
+        warnings.push(format!("Skipping table {table_name}, genORM cannot represent WITHOUT ROWID tables"));
+        return Ok(None)
+    }
+    if !is_identifier(table_name) || is_cxx_keyword(table_name) {
+        warnings.push(format!("Skipping table {table_name}, its name is not usable as a class name"));
+        return Ok(None)
+    }
+    // genORM addresses every row through an integer primary key named __id
+    let primary_keys = columns.iter().filter(|c| c.primary_key).collect::<Vec<_>>();
+    match primary_keys.as_slice() {
+        [pk] if pk.name == "__id" && pk.type_.eq_ignore_ascii_case("INTEGER") => {},
+        [pk] if pk.type_.eq_ignore_ascii_case("INTEGER") => {
+            warnings.push(format!("Skipping table {table_name}, it uses {} as its primary key but genORM expects it to be named __id", pk.name));
+            return Ok(None)
+        },
+        [] => {
+            warnings.push(format!("Skipping table {table_name}, it has no primary key but genORM expects an INTEGER PRIMARY KEY named __id"));
+            return Ok(None)
+        },
+        _ => {
+            warnings.push(format!("Skipping table {table_name}, genORM cannot represent its primary key and expects an INTEGER PRIMARY KEY named __id"));
+            return Ok(None)
+        },
+    }
+    // Generated members and accessors are named after the columns
+    if let Some(column) = columns.iter().find(|c| is_cxx_keyword(&c.name)) {
+        warnings.push(format!("Skipping table {table_name}, its column {} is a C++ keyword", column.name));
+        return Ok(None)
+    }
+    if check_constraints(sql).iter().any(|check| !columns.iter().any(|c| is_bool_check(check, c))) {
+        warnings.push(format!("Table {table_name} has CHECK constraints genORM does not import"));
+    }
+
+    let mut members = Vec::new();
+    for column in columns.iter().filter(|c| !c.primary_key) {
+        let column_name = &column.name;
+        if !is_identifier(column_name) || column_name.starts_with("__") {
+            warnings.push(format!("Skipping column {table_name}.{column_name}, its name is not usable as a member name"));
+            continue
+        }
+        let foreign_key = foreign_keys.iter().find(|fk| &fk.from == column_name);
+        match foreign_key {
+            Some(fk) if fk.column_count == 1 && fk.to.as_deref().is_none_or(|to| to == "__id") => {
+                references.push(ReferenceInfo {
+                    table: table_name.to_string(),
+                    column: column_name.clone(),
+                    target: fk.target.clone(),
+                    on_delete: (fk.on_delete != "NO ACTION").then(|| fk.on_delete.clone()),
+                });
+            },
+            Some(fk) => {
+                warnings.push(format!("Column {table_name}.{column_name} references {}, genORM can only reference the __id of another table", fk.target));
+            },
+            None => {}
+        }
+        members.push(Member {
+            name: column_name.clone(),
+            description: None,
+            type_: import_column_type(sql, column, table_name, warnings),
+            allow_null: (!column.not_null).then_some(true),
+            index: None,
+            unique: None,
+            storage: None,
+            precision: None,
+            on_delete: None,
+        });
+    }
+    if members.is_empty() {
+        warnings.push(format!("Skipping table {table_name}, it has no columns genORM can represent"));
+        return Ok(None)
+    }
+
+    let mut unique_constraints = Vec::new();
+    let mut indexes = Vec::new();
+    for index in query_indexes(connection, table_name)? {
+        let index_name = &index.name;
+        if index.origin == "pk" {
+            continue
+        }
+        if index.partial {
+            warnings.push(format!("Skipping partial index {index_name} of {table_name}"));
+            continue
+        }
+        let Some(column_names) = index.columns.iter().cloned().collect::<Option<Vec<String>>>() else {
+            warnings.push(format!("Skipping expression index {index_name} of {table_name}"));
+            continue
+        };
+        if !column_names.iter().all(|c| members.iter().any(|m| &m.name == c)) {
+            warnings.push(format!("Skipping index {index_name} of {table_name}, it covers columns that were not imported"));
+            continue
+        }
+        match (column_names.as_slice(), index.unique) {
+            ([column], true) => { members.iter_mut().filter(|m| &m.name == column).for_each(|m| m.unique = Some(true)); },
+            ([column], false) => { members.iter_mut().filter(|m| &m.name == column).for_each(|m| m.index = Some(true)); },
+            (_, true) => unique_constraints.push(UniqueConstraint { columns: column_names }),
+            (_, false) => indexes.push(Index { columns: column_names }),
+        }
+    }
+
+    Ok(Some(ObjectType {
+        name: table_name.to_string(),
+        description: None,
+        members,
+        unique_constraints: (!unique_constraints.is_empty()).then_some(unique_constraints),
+        indexes: (!indexes.is_empty()).then_some(indexes),
+        relations: None,
+    }))
+}
+
+/// Maps the declared column type following SQLite's type affinity rules
+fn import_column_type(sql: &str, column: &ColumnInfo, table_name: &str, warnings: &mut Vec<String>) -> String {
+    let declared_type = column.type_.to_uppercase();
+    let column_name = &column.name;
+    if is_bool_column(sql, column) || declared_type.contains("BOOL") {
+        String::from("BOOL")
+    } else if declared_type.contains("INT") {
+        String::from("INT64")
+    } else if declared_type.contains("CHAR") || declared_type.contains("CLOB") || declared_type.contains("TEXT") {
+        String::from("TEXT")
+    } else if declared_type.contains("BLOB") {
+        String::from("BYTEARRAY")
+    } else if declared_type.contains("REAL") || declared_type.contains("FLOA") || declared_type.contains("DOUB") {
+        String::from("FLOAT64")
+    } else if declared_type.is_empty() {
+        warnings.push(format!("Column {table_name}.{column_name} has no declared type, importing it as BYTEARRAY"));
+        String::from("BYTEARRAY")
+    } else {
+        warnings.push(format!("Column {table_name}.{column_name} has NUMERIC affinity ({}), importing it as FLOAT64", column.type_));
+        String::from("FLOAT64")
+    }
+}
+
+/// genORM stores booleans as integers restricted to 0 and 1
+fn is_bool_column(sql: &str, column: &ColumnInfo) -> bool {
+    check_constraints(sql).iter().any(|check| is_bool_check(check, column))
+}
+
+/// Matches `column IN (0, 1)` regardless of case, spacing and identifier quoting
+fn is_bool_check(check: &str, column: &ColumnInfo) -> bool {
+    let normalized = check.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '"' | '`' | '[' | ']'))
+        .collect::<String>()
+        .to_uppercase();
+    column.type_.eq_ignore_ascii_case("INTEGER") && normalized == format!("{}IN(0,1)", column.name.to_uppercase())
+}
+
+/// Returns the expressions of the CHECK constraints of a CREATE TABLE statement. String literals and quoted
+/// identifiers are skipped, so that e.g. a column named `checksum` is not mistaken for a constraint.
+fn check_constraints(sql: &str) -> Vec<String> {
+    let chars = sql.chars().collect::<Vec<_>>();
+    let mut checks = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            quote @ ('\'' | '"' | '`' | '[') => {
+                let closing = if quote == '[' { ']' } else { quote };
+                i += 1;
+                while i < chars.len() && chars[i] != closing {
+                    i += 1;
+                }
+                i += 1;
+            },
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word = chars[start..i].iter().collect::<String>();
+                let mut open = i;
+                while open < chars.len() && chars[open].is_whitespace() {
+                    open += 1;
+                }
+                if !word.eq_ignore_ascii_case("CHECK") || chars.get(open) != Some(&'(') {
+                    continue
+                }
+                // Finds the matching parenthesis, parentheses inside string literals do not count
+                let mut depth = 0;
+                let mut quote = None;
+                let mut end = open;
+                while end < chars.len() {
+                    match (quote, chars[end]) {
+                        (Some(q), c) if c == q => quote = None,
+                        (Some(_), _) => {},
+                        (None, c @ ('\'' | '"' | '`')) => quote = Some(c),
+                        (None, '[') => quote = Some(']'),
+                        (None, '(') => depth += 1,
+                        (None, ')') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break
+                            }
+                        },
+                        _ => {}
+                    }
+                    end += 1;
+                }
+                checks.push(chars[open + 1..end.min(chars.len())].iter().collect());
+                i = end + 1;
+            },
+            _ => i += 1,
+        }
+    }
+    checks
+}
+
+fn is_join_table(columns: &[ColumnInfo]) -> bool {
+    columns.len() == 2 && columns.iter().any(|c| c.name == "__owner_id") && columns.iter().any(|c| c.name == "__target_id")
+}
+
+fn query_columns(connection: &Connection, table_name: &str) -> Result<Vec<ColumnInfo>, String> {
+    let mut statement = connection
+        .prepare("SELECT name, type, \"notnull\", pk FROM pragma_table_info(?1) ORDER BY cid;")
+        .map_err(|e| e.to_string())?;
+    statement.query_map([table_name], |row| Ok(ColumnInfo { name: row.get(0)?, type_: row.get(1)?, not_null: row.get(2)?, primary_key: row.get::<_, i64>(3)? != 0 }))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())
+}
+
+fn query_foreign_keys(connection: &Connection, table_name: &str) -> Result<Vec<ForeignKeyInfo>, String> {
+    let mut statement = connection
+        .prepare("SELECT \"table\", \"from\", \"to\", on_delete, (SELECT COUNT(*) FROM pragma_foreign_key_list(?1) AS other WHERE other.id = fk.id) FROM pragma_foreign_key_list(?1) AS fk;")
+        .map_err(|e| e.to_string())?;
+    statement.query_map([table_name], |row| Ok(ForeignKeyInfo { target: row.get(0)?, from: row.get(1)?, to: row.get(2)?, on_delete: row.get(3)?, column_count: row.get::<_, i64>(4)? as usize }))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())
+}
+
+fn query_indexes(connection: &Connection, table_name: &str) -> Result<Vec<IndexInfo>, String> {
+    let mut list_statement = connection
+        .prepare("SELECT name, \"unique\", origin, partial FROM pragma_index_list(?1) ORDER BY seq DESC;")
+        .map_err(|e| e.to_string())?;
+    let index_list = list_statement.query_map([table_name], |row| Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?, row.get::<_, String>(2)?, row.get::<_, bool>(3)?)))
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| e.to_string())?;
+
+    let mut info_statement = connection
+        .prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno;")
+        .map_err(|e| e.to_string())?;
+    index_list.into_iter()
+        .map(|(name, unique, origin, partial)| {
+            let columns = info_statement.query_map([&name], |row| row.get::<_, Option<String>>(0))
+                .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+                .map_err(|e| e.to_string())?;
+            Ok(IndexInfo { name, columns, unique, origin, partial })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+    use crate::{Config, ObjectType};
+    use crate::cxx::format_schema_statements;
+    use crate::diff::diff_connection;
+    use super::import_connection;
+
+    const CONFIG: &str = r#"{
+        "genORM-config-version": 1,
+        "enums": [
+            { "name": "Color", "values": ["RED", "GREEN"], "storage": "TEXT" }
+        ],
+        "object-types": [
+            {
+                "name": "Owner",
+                "members": [
+                    { "name": "n", "type": "INT32", "index": true },
+                    { "name": "s", "type": "TEXT", "allow-null": true },
+                    { "name": "b", "type": "BOOL" },
+                    { "name": "f", "type": "FLOAT64" },
+                    { "name": "data", "type": "BYTEARRAY", "allow-null": true },
+                    { "name": "c", "type": "Color" }
+                ],
+                "indexes": [
+                    { "columns": ["n", "f"] }
+                ],
+                "relations": [
+                    { "name": "pets", "type": "many-to-many", "target": "Pet" }
+                ]
+            },
+            {
+                "name": "Pet",
+                "members": [
+                    { "name": "owner", "type": "REF(Owner)", "on-delete": "CASCADE" },
+                    { "name": "tag", "type": "TEXT", "unique": true }
+                ],
+                "unique-constraints": [
+                    { "columns": ["owner", "tag"] }
+                ]
+            }
+        ]
+    }"#;
+
+    fn import_sql(sql: &str) -> (Vec<ObjectType>, Vec<String>) {
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(sql).unwrap();
+        let (config, warnings) = import_connection(&connection).unwrap();
+        // Every imported config must be accepted by the generator
+        format_schema_statements(&[], &config.object_types).unwrap();
+        (config.object_types, warnings)
+    }
+
+    fn member_types(object_type: &ObjectType) -> Vec<(&str, &str)> {
+        object_type.members.iter().map(|m| (m.name.as_str(), m.type_.as_str())).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let config: Config = serde_json::from_str(CONFIG).unwrap();
+        let enums = config.enums.unwrap();
+        let connection = Connection::open_in_memory().unwrap();
+        for statement in format_schema_statements(&enums, &config.object_types).unwrap() {
+            connection.execute_batch(&statement).unwrap();
+        }
+
+        let (imported, warnings) = import_connection(&connection).unwrap();
+        assert_eq!(warnings, vec!["Add cxx-options to the config to generate code"]);
+        let owner = &imported.object_types[0];
+        assert_eq!(member_types(owner), vec![("n", "INT64"), ("s", "TEXT"), ("b", "BOOL"), ("f", "FLOAT64"), ("data", "BYTEARRAY"), ("c", "TEXT")]);
+        assert_eq!(owner.members[0].index, Some(true));
+        assert_eq!(owner.members[1].allow_null, Some(true));
+        assert_eq!(owner.indexes.as_ref().unwrap()[0].columns, vec!["n", "f"]);
+        let relation = &owner.relations.as_ref().unwrap()[0];
+        assert_eq!((relation.name.as_str(), relation.type_.as_str(), relation.target.as_str()), ("pets", "many-to-many", "Pet"));
+        let pet = &imported.object_types[1];
+        assert_eq!(member_types(pet), vec![("owner", "REF(Owner)"), ("tag", "TEXT")]);
+        assert_eq!(pet.members[0].on_delete.as_deref(), Some("CASCADE"));
+        assert_eq!(pet.members[1].unique, Some(true));
+        assert_eq!(pet.unique_constraints.as_ref().unwrap()[0].columns, vec!["owner", "tag"]);
+
+        // The imported config describes the same schema
+        let statements = format_schema_statements(&[], &imported.object_types).unwrap();
+        assert_eq!(diff_connection(&statements, &connection).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn bool_check_variants() {
+        let (object_types, warnings) = import_sql("CREATE TABLE T (__id INTEGER PRIMARY KEY NOT NULL, a INTEGER CHECK (a IN (0,1)), \
+            b INTEGER CHECK(\"b\" in ( 0 , 1 )), checksum TEXT, c INTEGER);");
+        assert_eq!(member_types(&object_types[0]), vec![("a", "BOOL"), ("b", "BOOL"), ("checksum", "TEXT"), ("c", "INT64")]);
+        assert!(!warnings.iter().any(|w| w.contains("CHECK")));
+    }
+
+    #[test]
+    fn other_check_constraints() {
+        let (_, warnings) = import_sql("CREATE TABLE T (__id INTEGER PRIMARY KEY NOT NULL, a INTEGER, s TEXT DEFAULT 'CHECK(', CHECK (a > 0));");
+        assert!(warnings.contains(&String::from("Table T has CHECK constraints genORM does not import")));
+        let (_, warnings) = import_sql("CREATE TABLE T (__id INTEGER PRIMARY KEY NOT NULL, s TEXT DEFAULT 'CHECK (1)');");
+        assert!(!warnings.iter().any(|w| w.contains("CHECK")));
+    }
+
+    #[test]
+    fn unsupported_tables_are_skipped() {
+        let (object_types, warnings) = import_sql("CREATE TABLE Keyed (id INTEGER PRIMARY KEY, n INTEGER); \
+            CREATE TABLE Keyless (n INTEGER); \
+            CREATE TABLE Keyword (__id INTEGER PRIMARY KEY NOT NULL, class TEXT); \
+            CREATE TABLE Valid (__id INTEGER PRIMARY KEY NOT NULL, n INTEGER);");
+        assert_eq!(object_types.iter().map(|o| o.name.as_str()).collect::<Vec<_>>(), vec!["Valid"]);
+        assert!(warnings.contains(&String::from("Skipping table Keyed, it uses id as its primary key but genORM expects it to be named __id")));
+        assert!(warnings.contains(&String::from("Skipping table Keyless, it has no primary key but genORM expects an INTEGER PRIMARY KEY named __id")));
+        assert!(warnings.contains(&String::from("Skipping table Keyword, its column class is a C++ keyword")));
+    }
+
+    #[test]
+    fn references_to_skipped_tables() {
+        let (object_types, warnings) = import_sql("CREATE TABLE Keyword (__id INTEGER PRIMARY KEY NOT NULL, class TEXT); \
+            CREATE TABLE Owner (__id INTEGER PRIMARY KEY NOT NULL, k INTEGER REFERENCES Keyword(__id), o INTEGER REFERENCES Owner(__id)); \
+            CREATE TABLE Owner_links (__owner_id INTEGER NOT NULL REFERENCES Owner(__id), __target_id INTEGER NOT NULL REFERENCES Keyword(__id));");
+        assert_eq!(object_types.len(), 1);
+        assert_eq!(member_types(&object_types[0]), vec![("k", "INT64"), ("o", "REF(Owner)")]);
+        assert!(object_types[0].relations.is_none());
+        assert!(warnings.contains(&String::from("Column Owner.k references Keyword, which was not imported, importing it as INT64")));
+    }
+
+    #[test]
+    fn set_null_on_non_null_references() {
+        let (object_types, warnings) = import_sql("CREATE TABLE Owner (__id INTEGER PRIMARY KEY NOT NULL, n INTEGER); \
+            CREATE TABLE Pet (__id INTEGER PRIMARY KEY NOT NULL, a INTEGER NOT NULL REFERENCES Owner(__id) ON DELETE SET NULL, \
+            b INTEGER REFERENCES Owner(__id) ON DELETE SET NULL, c INTEGER NOT NULL REFERENCES Owner(__id) ON DELETE SET DEFAULT);");
+        let pet = &object_types[1];
+        assert_eq!(pet.members.iter().map(|m| m.on_delete.as_deref()).collect::<Vec<_>>(), vec![None, Some("SET NULL"), None]);
+        assert!(warnings.contains(&String::from("Dropping ON DELETE SET NULL of Pet.a, genORM only allows it on nullable references")));
+        assert!(warnings.contains(&String::from("Dropping ON DELETE SET DEFAULT of Pet.c, genORM only allows it on nullable references")));
+    }
+}
@@ -1,72 +1,208 @@
 mod cxx;
+mod diff;
+mod import;
 mod util;
 
 use std::env;
 use std::fs::File;
 use std::io::BufReader;
-use serde::Deserialize;
-use crate::cxx::generate;
+use serde::{Deserialize, Serialize};
+use crate::cxx::{format_schema_statements, generate};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct CxxOptions {
     #[serde(rename = "file-prefix")]
     file_prefix: String,
 
     namespace: String,
 
-    #[serde(rename = "output-dir-root")]
+    #[serde(rename = "output-dir-root", skip_serializing_if = "Option::is_none")]
     output_dir_root: Option<String>,
 
-    #[serde(rename = "output-dir")]
+    #[serde(rename = "output-dir", skip_serializing_if = "Option::is_none")]
     output_dir: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct Member {
     name: String,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
 
     #[serde(rename = "type")]
     type_: String,
 
-    #[serde(rename = "allow-null")]
+    #[serde(rename = "allow-null", skip_serializing_if = "Option::is_none")]
     allow_null: Option<bool>,
     
-    index: Option<bool>
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unique: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    precision: Option<String>,
+
+    #[serde(rename = "on-delete", skip_serializing_if = "Option::is_none")]
+    on_delete: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct EnumType {
+    name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    values: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct UniqueConstraint {
+    columns: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Index {
+    columns: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Relation {
+    name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    #[serde(rename = "type")]
+    type_: String,
+
+    target: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    via: Option<String>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 struct ObjectType {
     name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     members: Vec<Member>,
+    #[serde(rename = "unique-constraints", skip_serializing_if = "Option::is_none")]
+    unique_constraints: Option<Vec<UniqueConstraint>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    indexes: Option<Vec<Index>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relations: Option<Vec<Relation>>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
+struct ColumnDefinition {
+    #[serde(rename = "type")]
+    type_: String,
+
+    #[serde(rename = "allow-null", skip_serializing_if = "Option::is_none")]
+    allow_null: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    precision: Option<String>,
+
+    #[serde(rename = "on-delete", skip_serializing_if = "Option::is_none")]
+    on_delete: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct MigrationStep {
+    #[serde(rename = "type")]
+    type_: String,
+
+    #[serde(rename = "object-type", skip_serializing_if = "Option::is_none")]
+    object_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    member: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<ColumnDefinition>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    statement: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Migration {
+    version: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    steps: Vec<MigrationStep>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 struct Config {
     #[serde(rename = "genORM-config-version")]
     config_version: u32,
 
-    #[serde(rename = "cxx-options")]
+    #[serde(rename = "cxx-options", skip_serializing_if = "Option::is_none")]
     cxx_options: Option<CxxOptions>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enums: Option<Vec<EnumType>>,
+
     #[serde(rename = "object-types")]
     object_types: Vec<ObjectType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    migrations: Option<Vec<Migration>>,
 }
 
 fn main() {
     // Ignore first arg
     let mut args = env::args().skip(1);
-    let fpath = match args.next() {
+    match args.next().as_deref() {
         None => {
-            eprintln!("Usage: genORM CONFIG-JSON");
+            eprintln!("Usage: genORM CONFIG-JSON\n       genORM diff CONFIG-JSON DATABASE-FILE\n       genORM import DATABASE-FILE");
             std::process::exit(1);
         },
-        Some(s) => { s }
-    };
+        Some("diff") => {
+            let (Some(config_path), Some(database_path)) = (args.next(), args.next()) else {
+                eprintln!("Usage: genORM diff CONFIG-JSON DATABASE-FILE");
+                std::process::exit(1);
+            };
+            run_diff(&config_path, &database_path);
+        },
+        Some("import") => {
+            let Some(database_path) = args.next() else {
+                eprintln!("Usage: genORM import DATABASE-FILE");
+                std::process::exit(1);
+            };
+            run_import(&database_path);
+        },
+        Some(s) => { run_generate(s) }
+    }
+}
 
-    let file = match File::open(&fpath) {
+fn load_config(fpath: &str) -> Config {
+    let file = match File::open(fpath) {
         Ok(f) => f,
         Err(e) => {
             eprintln!("Unable to open: {}, reason: {}", fpath, e);
@@ -80,25 +216,70 @@ fn main() {
             if config.config_version != 1 {
                 eprintln!("[WARN] Unsupported config version")
             }
-            if let Some(cxx_options) = config.cxx_options {
-                let output_dir = util::calculate_output_dir(&cxx_options.output_dir_root, &cxx_options.output_dir);
-                match output_dir {
-                    Ok(output_dir) => {
-                        if let Err(e) = generate(output_dir, &cxx_options, &config.object_types) {
-                            eprintln!("Error while generating C++ outputs: {:?}", e);
-                            std::process::exit(1);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Error while calculating output dir: {:?}", e);
-                        std::process::exit(1);
-                    }
+            config
+        },
+        Err(e) => {
+            eprintln!("Error while loading config: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_generate(fpath: &str) {
+    let config = load_config(fpath);
+    if let Some(cxx_options) = config.cxx_options {
+        let output_dir = util::calculate_output_dir(&cxx_options.output_dir_root, &cxx_options.output_dir);
+        match output_dir {
+            Ok(output_dir) => {
+                if let Err(e) = generate(output_dir, &cxx_options, config.enums.as_deref().unwrap_or_default(), &config.object_types, config.migrations.as_deref().unwrap_or_default()) {
+                    eprintln!("Error while generating C++ outputs: {:?}", e);
+                    std::process::exit(1);
                 }
             }
+            Err(e) => {
+                eprintln!("Error while calculating output dir: {:?}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Prints the differences between the database and the config, and exits with 1 if there are any.
+fn run_diff(config_path: &str, database_path: &str) {
+    let config = load_config(config_path);
+    let differences = format_schema_statements(config.enums.as_deref().unwrap_or_default(), &config.object_types)
+        .and_then(|statements| diff::diff(&statements, database_path));
+    match differences {
+        Ok(differences) if differences.is_empty() => {
+            println!("No differences");
+        },
+        Ok(differences) => {
+            for difference in differences {
+                println!("{difference}");
+            }
+            std::process::exit(1);
         },
         Err(e) => {
-            eprintln!("Error while loading config: {:?}", e);
+            eprintln!("Error while comparing the database: {:?}", e);
             std::process::exit(1);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Prints a config describing the database, warnings go to stderr so that the output can be redirected to a file.
+fn run_import(database_path: &str) {
+    let config = import::import(database_path)
+        .and_then(|(config, warnings)| serde_json::to_string_pretty(&config).map(|json| (json, warnings)).map_err(|e| e.to_string()));
+    match config {
+        Ok((json, warnings)) => {
+            for warning in warnings {
+                eprintln!("[WARN] {warning}");
+            }
+            println!("{json}");
+        },
+        Err(e) => {
+            eprintln!("Error while importing the database: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+}
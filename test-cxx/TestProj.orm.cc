@@ -1,11 +1,52 @@
 // Auto-generated file. Changes will be overridden.
 #include "TestProj.orm.h"
 
+genORM::value_variant testproj::Color_to_value(const Color value) {
+    switch (value) {
+        case Color::RED: return int32_t{0};
+        case Color::GREEN: return int32_t{1};
+        case Color::BLUE: return int32_t{2};
+    }
+    throw std::logic_error("Implementation error");
+}
+std::expected<testproj::Color, genORM::error> testproj::Color_from_value(const genORM::value_variant& value) {
+    if (const auto* integer = std::get_if<int32_t>(&value)) {
+        switch (*integer) {
+            case 0: return Color::RED;
+            case 1: return Color::GREEN;
+            case 2: return Color::BLUE;
+            default: return std::unexpected{genORM::error{genORM::error_category::invalid_value, "Unknown Color value: " + std::to_string(*integer)}};
+        }
+    }
+    return std::unexpected{genORM::error{genORM::error_category::invalid_value, "Unexpected Color value type"}};
+}
+genORM::value_variant testproj::Shape_to_value(const Shape value) {
+    switch (value) {
+        case Shape::CIRCLE: return std::string{"CIRCLE"};
+        case Shape::SQUARE: return std::string{"SQUARE"};
+    }
+    throw std::logic_error("Implementation error");
+}
+std::expected<testproj::Shape, genORM::error> testproj::Shape_from_value(const genORM::value_variant& value) {
+    if (const auto* text = std::get_if<std::string>(&value)) {
+        if (*text == "CIRCLE") { return Shape::CIRCLE; }
+        if (*text == "SQUARE") { return Shape::SQUARE; }
+        return std::unexpected{genORM::error{genORM::error_category::invalid_value, "Unknown Shape value: " + *text}};
+    }
+    return std::unexpected{genORM::error{genORM::error_category::invalid_value, "Unexpected Shape value type"}};
+}
 testproj::MyObject::MyObject(genORM::database& __db, uint64_t __id, int32_t i, std::optional<int32_t> io, std::vector<uint8_t> ba)
     : object(__db, __id), _i(i), _io(io), _ba(std::move(ba)) {}
 
-std::expected<testproj::MyObject, std::string> testproj::MyObject::create(genORM::database& __db, int32_t i, std::optional<int32_t> io, std::vector<uint8_t> ba) {
-    static constexpr std::string_view create_table_statement = "CREATE TABLE IF NOT EXISTS MyObject (__id INTEGER PRIMARY KEY NOT NULL, i INTEGER NOT NULL DEFAULT 0, io INTEGER  DEFAULT NULL, ba BLOB) STRICT;";
+std::expected<testproj::MyObject, genORM::error> testproj::MyObject::from_row(genORM::database& __db, std::vector<genORM::value_variant>& row) {
+    return MyObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
+        std::get<int32_t>(row[1]),
+        std::holds_alternative<int32_t>(row[2]) ? std::get<int32_t>(row[2]) : std::optional<int32_t>{},
+        std::holds_alternative<std::vector<uint8_t>>(row[3]) ? std::move(std::get<std::vector<uint8_t>>(row[3])) : std::vector<uint8_t>{}
+    };
+}
+std::expected<void, genORM::error> testproj::MyObject::ensure_schema(genORM::database& __db) {
+    static constexpr std::string_view create_table_statement = "CREATE TABLE IF NOT EXISTS MyObject (__id INTEGER PRIMARY KEY NOT NULL, i INTEGER NOT NULL DEFAULT 0, io INTEGER  DEFAULT NULL, ba BLOB NOT NULL DEFAULT x'') STRICT;";
     if (auto create_table_result = create_table_if_not_exists(__db, create_table_statement); not create_table_result) { return std::unexpected{std::move(create_table_result.error())}; }
     {
         static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyObject_i ON MyObject(i);";
@@ -15,7 +56,24 @@ std::expected<testproj::MyObject, std::string> testproj::MyObject::create(genORM
         static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyObject_io ON MyObject(io);";
         if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
     }
-    static constexpr std::string_view insert_statement = "INSERT INTO MyObject VALUES (NULL, ?, ?, ?);";
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyObject_ba ON MyObject(ba);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyObject_i_io ON MyObject(i, io);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_join_table_statement = "CREATE TABLE IF NOT EXISTS MyObject_tags (__owner_id INTEGER NOT NULL REFERENCES MyObject(__id) ON DELETE CASCADE, __target_id INTEGER NOT NULL REFERENCES MyThirdObject(__id) ON DELETE CASCADE, PRIMARY KEY (__owner_id, __target_id)) STRICT;";
+        if (auto create_table_result = create_table_if_not_exists(__db, create_join_table_statement); not create_table_result) { return std::unexpected{std::move(create_table_result.error())}; }
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyObject_tags___target_id ON MyObject_tags(__target_id);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    return {};
+}
+std::expected<testproj::MyObject, genORM::error> testproj::MyObject::create(genORM::database& __db, int32_t i, std::optional<int32_t> io, std::vector<uint8_t> ba) {
+    static const genORM::cached_statement insert_statement{"INSERT INTO MyObject (i, io, ba) VALUES (?, ?, ?);"};
     const auto binder = [&](int value_index) -> genORM::value_variant {
         if (value_index == 1) { return i; }
         if (value_index == 2) { return io ? genORM::value_variant{*io} : std::monostate{}; }
@@ -28,16 +86,12 @@ std::expected<testproj::MyObject, std::string> testproj::MyObject::create(genORM
         return std::unexpected{std::move(create_table_result.error())};
     }
 }
-std::expected<std::optional<testproj::MyObject>, std::string> testproj::MyObject::find_by_rowid(genORM::database& __db, const uint64_t __id) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MyObject WHERE __id = ? LIMIT 1;";
+std::expected<std::optional<testproj::MyObject>, genORM::error> testproj::MyObject::find_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE __id = ? LIMIT 1;"};
     if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); },
             std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
         if (*select_result) {
-            return MyObject{__db, __id,
-                std::get<int32_t>((**select_result)[1]),
-                std::holds_alternative<int32_t>((**select_result)[2]) ? std::get<int32_t>((**select_result)[2]) : std::optional<int32_t>{},
-                std::holds_alternative<std::vector<uint8_t>>((**select_result)[3]) ? std::move(std::get<std::vector<uint8_t>>((**select_result)[3])) : std::vector<uint8_t>{}
-            };
+            return from_row(__db, **select_result);
         } else {
             return std::nullopt;
         }
@@ -45,16 +99,12 @@ std::expected<std::optional<testproj::MyObject>, std::string> testproj::MyObject
         return std::unexpected{std::move(select_result.error())};
     }
 }
-std::expected<std::optional<testproj::MyObject>, std::string> testproj::MyObject::find_first_by_i(genORM::database& __db, const int32_t i) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MyObject WHERE i = ? LIMIT 1;";
+std::expected<std::optional<testproj::MyObject>, genORM::error> testproj::MyObject::find_first_by_i(genORM::database& __db, const int32_t i) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE i = ? LIMIT 1;"};
     if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return i; },
             std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
         if (*select_result) {
-            return MyObject{__db, static_cast<uint64_t>(std::get<int64_t>((**select_result)[0])),
-                std::get<int32_t>((**select_result)[1]),
-                std::holds_alternative<int32_t>((**select_result)[2]) ? std::get<int32_t>((**select_result)[2]) : std::optional<int32_t>{},
-                std::holds_alternative<std::vector<uint8_t>>((**select_result)[3]) ? std::move(std::get<std::vector<uint8_t>>((**select_result)[3])) : std::vector<uint8_t>{}
-            };
+            return from_row(__db, **select_result);
         } else {
             return std::nullopt;
         }
@@ -62,16 +112,25 @@ std::expected<std::optional<testproj::MyObject>, std::string> testproj::MyObject
         return std::unexpected{std::move(select_result.error())};
     }
 }
-std::expected<std::optional<testproj::MyObject>, std::string> testproj::MyObject::find_first_by_io(genORM::database& __db, const std::optional<int32_t> io) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MyObject WHERE io = ? LIMIT 1;";
+std::expected<std::optional<testproj::MyObject>, genORM::error> testproj::MyObject::find_first_by_io(genORM::database& __db, const std::optional<int32_t> io) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE io IS ? LIMIT 1;"};
     if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return io ? genORM::value_variant{*io} : std::monostate{}; },
             std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
         if (*select_result) {
-            return MyObject{__db, static_cast<uint64_t>(std::get<int64_t>((**select_result)[0])),
-                std::get<int32_t>((**select_result)[1]),
-                std::holds_alternative<int32_t>((**select_result)[2]) ? std::get<int32_t>((**select_result)[2]) : std::optional<int32_t>{},
-                std::holds_alternative<std::vector<uint8_t>>((**select_result)[3]) ? std::move(std::get<std::vector<uint8_t>>((**select_result)[3])) : std::vector<uint8_t>{}
-            };
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyObject>, genORM::error> testproj::MyObject::find_first_by_ba(genORM::database& __db, const std::vector<uint8_t> ba) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE ba = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return ba; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
         } else {
             return std::nullopt;
         }
@@ -79,18 +138,18 @@ std::expected<std::optional<testproj::MyObject>, std::string> testproj::MyObject
         return std::unexpected{std::move(select_result.error())};
     }
 }
-std::expected<std::vector<testproj::MyObject>, std::string> testproj::MyObject::find_all_by_i(genORM::database& __db, const int32_t i) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MyObject WHERE i = ?;";
+std::expected<std::vector<testproj::MyObject>, genORM::error> testproj::MyObject::find_all_by_i(genORM::database& __db, const int32_t i) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE i = ?;"};
     if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return i; },
             std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
         std::vector<MyObject> found_objects;
         if (auto& rows = *select_result; not rows.empty()) {
             for (auto& row : rows) {
-                found_objects.emplace_back(MyObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
-                    std::get<int32_t>(row[1]),
-                    std::holds_alternative<int32_t>(row[2]) ? std::get<int32_t>(row[2]) : std::optional<int32_t>{},
-                    std::holds_alternative<std::vector<uint8_t>>(row[3]) ? std::move(std::get<std::vector<uint8_t>>(row[3])) : std::vector<uint8_t>{}
-                });
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
             }
         }
         return found_objects;
@@ -98,18 +157,104 @@ std::expected<std::vector<testproj::MyObject>, std::string> testproj::MyObject::
         return std::unexpected{std::move(select_result.error())};
     }
 }
-std::expected<std::vector<testproj::MyObject>, std::string> testproj::MyObject::find_all_by_io(genORM::database& __db, const std::optional<int32_t> io) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MyObject WHERE io = ?;";
+std::expected<genORM::object_range<testproj::MyObject>, genORM::error> testproj::MyObject::iterate_all_by_i(genORM::database& __db, const int32_t i) {
+    static constexpr std::string_view select_statement = "SELECT __id, i, io, ba FROM MyObject WHERE i = ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return i; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        return genORM::object_range<MyObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyObject>, genORM::error> testproj::MyObject::find_all_by_io(genORM::database& __db, const std::optional<int32_t> io) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE io IS ?;"};
     if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return io ? genORM::value_variant{*io} : std::monostate{}; },
             std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
         std::vector<MyObject> found_objects;
         if (auto& rows = *select_result; not rows.empty()) {
             for (auto& row : rows) {
-                found_objects.emplace_back(MyObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
-                    std::get<int32_t>(row[1]),
-                    std::holds_alternative<int32_t>(row[2]) ? std::get<int32_t>(row[2]) : std::optional<int32_t>{},
-                    std::holds_alternative<std::vector<uint8_t>>(row[3]) ? std::move(std::get<std::vector<uint8_t>>(row[3])) : std::vector<uint8_t>{}
-                });
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MyObject>, genORM::error> testproj::MyObject::iterate_all_by_io(genORM::database& __db, const std::optional<int32_t> io) {
+    static constexpr std::string_view select_statement = "SELECT __id, i, io, ba FROM MyObject WHERE io IS ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return io ? genORM::value_variant{*io} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        return genORM::object_range<MyObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyObject>, genORM::error> testproj::MyObject::find_all_by_ba(genORM::database& __db, const std::vector<uint8_t> ba) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE ba = ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return ba; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        std::vector<MyObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MyObject>, genORM::error> testproj::MyObject::iterate_all_by_ba(genORM::database& __db, const std::vector<uint8_t> ba) {
+    static constexpr std::string_view select_statement = "SELECT __id, i, io, ba FROM MyObject WHERE ba = ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return ba; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        return genORM::object_range<MyObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyObject>, genORM::error> testproj::MyObject::find_first_by_i_and_io(genORM::database& __db, const int32_t i, const std::optional<int32_t> io) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE i = ? AND io IS ? LIMIT 1;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return i; }
+        if (value_index == 2) { return io ? genORM::value_variant{*io} : std::monostate{}; }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto select_result = select_one(__db, select_statement, 2, binder,
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyObject>, genORM::error> testproj::MyObject::find_all_by_i_and_io(genORM::database& __db, const int32_t i, const std::optional<int32_t> io) {
+    static const genORM::cached_statement select_statement{"SELECT __id, i, io, ba FROM MyObject WHERE i = ? AND io IS ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return i; }
+        if (value_index == 2) { return io ? genORM::value_variant{*io} : std::monostate{}; }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto select_result = select_all(__db, select_statement, 2, binder,
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        std::vector<MyObject> found_objects;
+        for (auto& row : *select_result) {
+            if (auto found_object = from_row(__db, row)) {
+                found_objects.emplace_back(std::move(*found_object));
+            } else {
+                return std::unexpected{std::move(found_object.error())};
             }
         }
         return found_objects;
@@ -117,37 +262,743 @@ std::expected<std::vector<testproj::MyObject>, std::string> testproj::MyObject::
         return std::unexpected{std::move(select_result.error())};
     }
 }
-testproj::MySecondObject::MySecondObject(genORM::database& __db, uint64_t __id, int64_t l, std::optional<int64_t> lo)
-    : object(__db, __id), _l(l), _lo(lo) {}
+std::expected<uint64_t, genORM::error> testproj::MyObject::delete_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyObject WHERE __id = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); });
+}
+std::expected<uint64_t, genORM::error> testproj::MyObject::delete_all_by_i(genORM::database& __db, const int32_t i) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyObject WHERE i = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return i; });
+}
+std::expected<uint64_t, genORM::error> testproj::MyObject::delete_all_by_io(genORM::database& __db, const std::optional<int32_t> io) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyObject WHERE io IS ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return io ? genORM::value_variant{*io} : std::monostate{}; });
+}
+std::expected<uint64_t, genORM::error> testproj::MyObject::delete_all_by_ba(genORM::database& __db, const std::vector<uint8_t> ba) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyObject WHERE ba = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return ba; });
+}
+std::expected<void, genORM::error> testproj::MyObject::save() {
+    static const genORM::cached_statement update_statement{"UPDATE MyObject SET i = CASE WHEN ? THEN ? ELSE i END, io = CASE WHEN ? THEN ? ELSE io END, ba = CASE WHEN ? THEN ? ELSE ba END WHERE __id = ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(_changed.test(0)); }
+        if (value_index == 2) { return _i; }
+        if (value_index == 3) { return static_cast<int64_t>(_changed.test(1)); }
+        if (value_index == 4) { return _io ? genORM::value_variant{*_io} : std::monostate{}; }
+        if (value_index == 5) { return static_cast<int64_t>(_changed.test(2)); }
+        if (value_index == 6) { return _ba; }
+        if (value_index == 7) { return static_cast<int64_t>(get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto update_result = update_table(get_database(), update_statement, 7, binder); not update_result) {
+        return std::unexpected{std::move(update_result.error())};
+    } else if (*update_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    _changed.reset();
+    return {};
+}
+std::expected<void, genORM::error> testproj::MyObject::remove() const {
+    if (auto delete_result = delete_by_rowid(get_database(), get_rowid()); not delete_result) {
+        return std::unexpected{std::move(delete_result.error())};
+    } else if (*delete_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    return {};
+}
+std::expected<std::vector<testproj::MyFourthObject>, genORM::error> testproj::MyObject::get_children() const {
+    return MyFourthObject::find_all_by_o(get_database(), get_rowid());
+}
+std::expected<std::vector<testproj::MyThirdObject>, genORM::error> testproj::MyObject::get_tags() const {
+    static const genORM::cached_statement select_statement{"SELECT MyThirdObject.__id, MyThirdObject.c, MyThirdObject.sh, MyThirdObject.ts, MyThirdObject.tt FROM MyThirdObject JOIN MyObject_tags ON MyThirdObject.__id = MyObject_tags.__target_id WHERE MyObject_tags.__owner_id = ?;"};
+    if (auto select_result = select_all(get_database(), select_statement, 1, [this](int) -> genORM::value_variant { return static_cast<int64_t>(get_rowid()); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        std::vector<MyThirdObject> found_objects;
+        for (auto& row : *select_result) {
+            if (auto found_object = MyThirdObject::from_row(get_database(), row)) {
+                found_objects.emplace_back(std::move(*found_object));
+            } else {
+                return std::unexpected{std::move(found_object.error())};
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<void, genORM::error> testproj::MyObject::link_tags(const MyThirdObject& other) const {
+    // Linking twice is not an error, the pair is only stored once
+    static const genORM::cached_statement insert_statement{"INSERT OR IGNORE INTO MyObject_tags VALUES (?, ?);"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(get_rowid()); }
+        if (value_index == 2) { return static_cast<int64_t>(other.get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto insert_result = insert_into_table(get_database(), insert_statement, 2, binder); not insert_result) {
+        return std::unexpected{std::move(insert_result.error())};
+    }
+    return {};
+}
+std::expected<void, genORM::error> testproj::MyObject::unlink_tags(const MyThirdObject& other) const {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyObject_tags WHERE __owner_id = ? AND __target_id = ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(get_rowid()); }
+        if (value_index == 2) { return static_cast<int64_t>(other.get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto delete_result = delete_from_table(get_database(), delete_statement, 2, binder); not delete_result) {
+        return std::unexpected{std::move(delete_result.error())};
+    }
+    return {};
+}
+testproj::MySecondObject::MySecondObject(genORM::database& __db, uint64_t __id, int64_t l, std::optional<int64_t> lo, std::string s, std::optional<std::string> so, float f, std::optional<double> d, bool b, std::optional<std::vector<uint8_t>> bo)
+    : object(__db, __id), _l(l), _lo(lo), _s(std::move(s)), _so(std::move(so)), _f(f), _d(d), _b(b), _bo(std::move(bo)) {}
 
-std::expected<testproj::MySecondObject, std::string> testproj::MySecondObject::create(genORM::database& __db, int64_t l, std::optional<int64_t> lo) {
-    static constexpr std::string_view create_table_statement = "CREATE TABLE IF NOT EXISTS MySecondObject (__id INTEGER PRIMARY KEY NOT NULL, l INTEGER NOT NULL DEFAULT 0, lo INTEGER  DEFAULT NULL) STRICT;";
+std::expected<testproj::MySecondObject, genORM::error> testproj::MySecondObject::from_row(genORM::database& __db, std::vector<genORM::value_variant>& row) {
+    return MySecondObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
+        std::get<int64_t>(row[1]),
+        std::holds_alternative<int64_t>(row[2]) ? std::get<int64_t>(row[2]) : std::optional<int64_t>{},
+        std::move(std::get<std::string>(row[3])),
+        std::holds_alternative<std::string>(row[4]) ? std::move(std::get<std::string>(row[4])) : std::optional<std::string>{},
+        static_cast<float>(std::get<double>(row[5])),
+        std::holds_alternative<double>(row[6]) ? std::get<double>(row[6]) : std::optional<double>{},
+        std::get<int32_t>(row[7]) != 0,
+        std::holds_alternative<std::vector<uint8_t>>(row[8]) ? std::move(std::get<std::vector<uint8_t>>(row[8])) : std::optional<std::vector<uint8_t>>{}
+    };
+}
+std::expected<void, genORM::error> testproj::MySecondObject::ensure_schema(genORM::database& __db) {
+    static constexpr std::string_view create_table_statement = "CREATE TABLE IF NOT EXISTS MySecondObject (__id INTEGER PRIMARY KEY NOT NULL, l INTEGER NOT NULL DEFAULT 0, lo INTEGER  DEFAULT NULL, s TEXT NOT NULL DEFAULT '', so TEXT  DEFAULT NULL, f REAL NOT NULL DEFAULT 0.0, d REAL  DEFAULT NULL, b INTEGER NOT NULL DEFAULT 0 CHECK(b IN (0, 1)), bo BLOB  DEFAULT NULL) STRICT;";
     if (auto create_table_result = create_table_if_not_exists(__db, create_table_statement); not create_table_result) { return std::unexpected{std::move(create_table_result.error())}; }
     {
         static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MySecondObject_lo ON MySecondObject(lo);";
         if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
     }
-    static constexpr std::string_view insert_statement = "INSERT INTO MySecondObject VALUES (NULL, ?, ?);";
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MySecondObject_s ON MySecondObject(s);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MySecondObject_b ON MySecondObject(b);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE UNIQUE INDEX IF NOT EXISTS UniqueIndex_MySecondObject_l ON MySecondObject(l);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    return {};
+}
+std::expected<testproj::MySecondObject, genORM::error> testproj::MySecondObject::create(genORM::database& __db, int64_t l, std::optional<int64_t> lo, std::string s, std::optional<std::string> so, float f, std::optional<double> d, bool b, std::optional<std::vector<uint8_t>> bo) {
+    static const genORM::cached_statement insert_statement{"INSERT INTO MySecondObject (l, lo, s, so, f, d, b, bo) VALUES (?, ?, ?, ?, ?, ?, ?, ?);"};
     const auto binder = [&](int value_index) -> genORM::value_variant {
         if (value_index == 1) { return l; }
         if (value_index == 2) { return lo ? genORM::value_variant{*lo} : std::monostate{}; }
+        if (value_index == 3) { return s; }
+        if (value_index == 4) { return so ? genORM::value_variant{*so} : std::monostate{}; }
+        if (value_index == 5) { return static_cast<double>(f); }
+        if (value_index == 6) { return d ? genORM::value_variant{*d} : std::monostate{}; }
+        if (value_index == 7) { return static_cast<int32_t>(b); }
+        if (value_index == 8) { return bo ? genORM::value_variant{*bo} : std::monostate{}; }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto create_table_result = insert_into_table(__db, insert_statement, 8, binder)) {
+        return MySecondObject{__db, *create_table_result, l, lo, std::move(s), std::move(so), f, d, b, std::move(bo)};
+    } else {
+        return std::unexpected{std::move(create_table_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE __id = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_first_by_lo(genORM::database& __db, const std::optional<int64_t> lo) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE lo IS ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return lo ? genORM::value_variant{*lo} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_first_by_s(genORM::database& __db, const std::string s) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE s = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return s; },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_first_by_b(genORM::database& __db, const bool b) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE b = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int32_t>(b); },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_all_by_lo(genORM::database& __db, const std::optional<int64_t> lo) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE lo IS ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return lo ? genORM::value_variant{*lo} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        std::vector<MySecondObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::iterate_all_by_lo(genORM::database& __db, const std::optional<int64_t> lo) {
+    static constexpr std::string_view select_statement = "SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE lo IS ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return lo ? genORM::value_variant{*lo} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        return genORM::object_range<MySecondObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_all_by_s(genORM::database& __db, const std::string s) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE s = ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return s; },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        std::vector<MySecondObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::iterate_all_by_s(genORM::database& __db, const std::string s) {
+    static constexpr std::string_view select_statement = "SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE s = ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return s; },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        return genORM::object_range<MySecondObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_all_by_b(genORM::database& __db, const bool b) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE b = ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int32_t>(b); },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        std::vector<MySecondObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::iterate_all_by_b(genORM::database& __db, const bool b) {
+    static constexpr std::string_view select_statement = "SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE b = ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int32_t>(b); },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        return genORM::object_range<MySecondObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MySecondObject>, genORM::error> testproj::MySecondObject::find_by_l(genORM::database& __db, const int64_t l) {
+    static const genORM::cached_statement select_statement{"SELECT __id, l, lo, s, so, f, d, b, bo FROM MySecondObject WHERE l = ? LIMIT 1;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return l; }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto select_result = select_one(__db, select_statement, 1, binder,
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}, std::string{}, std::string{}, double{}, double{}, int32_t{}, std::vector<uint8_t>{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<uint64_t, genORM::error> testproj::MySecondObject::delete_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MySecondObject WHERE __id = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); });
+}
+std::expected<uint64_t, genORM::error> testproj::MySecondObject::delete_all_by_l(genORM::database& __db, const int64_t l) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MySecondObject WHERE l = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return l; });
+}
+std::expected<uint64_t, genORM::error> testproj::MySecondObject::delete_all_by_lo(genORM::database& __db, const std::optional<int64_t> lo) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MySecondObject WHERE lo IS ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return lo ? genORM::value_variant{*lo} : std::monostate{}; });
+}
+std::expected<uint64_t, genORM::error> testproj::MySecondObject::delete_all_by_s(genORM::database& __db, const std::string s) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MySecondObject WHERE s = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return s; });
+}
+std::expected<uint64_t, genORM::error> testproj::MySecondObject::delete_all_by_b(genORM::database& __db, const bool b) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MySecondObject WHERE b = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int32_t>(b); });
+}
+std::expected<void, genORM::error> testproj::MySecondObject::save() {
+    static const genORM::cached_statement update_statement{"UPDATE MySecondObject SET l = CASE WHEN ? THEN ? ELSE l END, lo = CASE WHEN ? THEN ? ELSE lo END, s = CASE WHEN ? THEN ? ELSE s END, so = CASE WHEN ? THEN ? ELSE so END, f = CASE WHEN ? THEN ? ELSE f END, d = CASE WHEN ? THEN ? ELSE d END, b = CASE WHEN ? THEN ? ELSE b END, bo = CASE WHEN ? THEN ? ELSE bo END WHERE __id = ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(_changed.test(0)); }
+        if (value_index == 2) { return _l; }
+        if (value_index == 3) { return static_cast<int64_t>(_changed.test(1)); }
+        if (value_index == 4) { return _lo ? genORM::value_variant{*_lo} : std::monostate{}; }
+        if (value_index == 5) { return static_cast<int64_t>(_changed.test(2)); }
+        if (value_index == 6) { return _s; }
+        if (value_index == 7) { return static_cast<int64_t>(_changed.test(3)); }
+        if (value_index == 8) { return _so ? genORM::value_variant{*_so} : std::monostate{}; }
+        if (value_index == 9) { return static_cast<int64_t>(_changed.test(4)); }
+        if (value_index == 10) { return static_cast<double>(_f); }
+        if (value_index == 11) { return static_cast<int64_t>(_changed.test(5)); }
+        if (value_index == 12) { return _d ? genORM::value_variant{*_d} : std::monostate{}; }
+        if (value_index == 13) { return static_cast<int64_t>(_changed.test(6)); }
+        if (value_index == 14) { return static_cast<int32_t>(_b); }
+        if (value_index == 15) { return static_cast<int64_t>(_changed.test(7)); }
+        if (value_index == 16) { return _bo ? genORM::value_variant{*_bo} : std::monostate{}; }
+        if (value_index == 17) { return static_cast<int64_t>(get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto update_result = update_table(get_database(), update_statement, 17, binder); not update_result) {
+        return std::unexpected{std::move(update_result.error())};
+    } else if (*update_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    _changed.reset();
+    return {};
+}
+std::expected<void, genORM::error> testproj::MySecondObject::remove() const {
+    if (auto delete_result = delete_by_rowid(get_database(), get_rowid()); not delete_result) {
+        return std::unexpected{std::move(delete_result.error())};
+    } else if (*delete_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    return {};
+}
+std::expected<std::vector<testproj::MyFifthObject>, genORM::error> testproj::MySecondObject::get_details() const {
+    std::vector<MyFifthObject> found_objects;
+    if (auto found_object = MyFifthObject::find_by_owner(get_database(), get_rowid()); not found_object) {
+        return std::unexpected{std::move(found_object.error())};
+    } else if (*found_object) {
+        found_objects.emplace_back(std::move(**found_object));
+    }
+    return found_objects;
+}
+testproj::MyThirdObject::MyThirdObject(genORM::database& __db, uint64_t __id, Color c, std::optional<Shape> sh, std::chrono::sys_time<std::chrono::microseconds> ts, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt)
+    : object(__db, __id), _c(c), _sh(sh), _ts(ts), _tt(tt) {}
+
+std::expected<testproj::MyThirdObject, genORM::error> testproj::MyThirdObject::from_row(genORM::database& __db, std::vector<genORM::value_variant>& row) {
+    const auto __c = Color_from_value(row[1]);
+    if (not __c) { return std::unexpected{__c.error()}; }
+    std::optional<Shape> __sh;
+    if (not std::holds_alternative<std::monostate>(row[2])) { if (auto value = Shape_from_value(row[2])) { __sh = *value; } else { return std::unexpected{std::move(value.error())}; } }
+    std::optional<std::chrono::sys_time<std::chrono::milliseconds>> __tt;
+    if (const auto* text = std::get_if<std::string>(&row[4])) { if (auto value = genORM::parse_timestamp<std::chrono::milliseconds>(*text)) { __tt = *value; } else { return std::unexpected{std::move(value.error())}; } }
+    return MyThirdObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
+        *__c,
+        __sh,
+        std::chrono::sys_time<std::chrono::microseconds>{std::chrono::microseconds{std::get<int64_t>(row[3])}},
+        __tt
+    };
+}
+std::expected<void, genORM::error> testproj::MyThirdObject::ensure_schema(genORM::database& __db) {
+    static constexpr std::string_view create_table_statement = "CREATE TABLE IF NOT EXISTS MyThirdObject (__id INTEGER PRIMARY KEY NOT NULL, c INTEGER NOT NULL DEFAULT 0, sh TEXT  DEFAULT NULL, ts INTEGER NOT NULL DEFAULT 0, tt TEXT  DEFAULT NULL) STRICT;";
+    if (auto create_table_result = create_table_if_not_exists(__db, create_table_statement); not create_table_result) { return std::unexpected{std::move(create_table_result.error())}; }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyThirdObject_c ON MyThirdObject(c);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyThirdObject_sh ON MyThirdObject(sh);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyThirdObject_ts ON MyThirdObject(ts);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyThirdObject_tt ON MyThirdObject(tt);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE UNIQUE INDEX IF NOT EXISTS UniqueIndex_MyThirdObject_c_sh ON MyThirdObject(c, sh);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    return {};
+}
+std::expected<testproj::MyThirdObject, genORM::error> testproj::MyThirdObject::create(genORM::database& __db, Color c, std::optional<Shape> sh, std::chrono::sys_time<std::chrono::microseconds> ts, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt) {
+    static const genORM::cached_statement insert_statement{"INSERT INTO MyThirdObject (c, sh, ts, tt) VALUES (?, ?, ?, ?);"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return Color_to_value(c); }
+        if (value_index == 2) { return sh ? genORM::value_variant{Shape_to_value(*sh)} : std::monostate{}; }
+        if (value_index == 3) { return static_cast<int64_t>(ts.time_since_epoch().count()); }
+        if (value_index == 4) { return tt ? genORM::value_variant{genORM::format_timestamp((*tt), 3)} : std::monostate{}; }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto create_table_result = insert_into_table(__db, insert_statement, 4, binder)) {
+        return MyThirdObject{__db, *create_table_result, c, sh, ts, tt};
+    } else {
+        return std::unexpected{std::move(create_table_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE __id = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_first_by_c(genORM::database& __db, const Color c) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE c = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return Color_to_value(c); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_first_by_sh(genORM::database& __db, const std::optional<Shape> sh) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE sh IS ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return sh ? genORM::value_variant{Shape_to_value(*sh)} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_first_by_ts(genORM::database& __db, const std::chrono::sys_time<std::chrono::microseconds> ts) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE ts = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(ts.time_since_epoch().count()); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_first_by_tt(genORM::database& __db, const std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE tt IS ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return tt ? genORM::value_variant{genORM::format_timestamp((*tt), 3)} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_all_by_c(genORM::database& __db, const Color c) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE c = ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return Color_to_value(c); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        std::vector<MyThirdObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::iterate_all_by_c(genORM::database& __db, const Color c) {
+    static constexpr std::string_view select_statement = "SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE c = ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return Color_to_value(c); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        return genORM::object_range<MyThirdObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_all_by_sh(genORM::database& __db, const std::optional<Shape> sh) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE sh IS ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return sh ? genORM::value_variant{Shape_to_value(*sh)} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        std::vector<MyThirdObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::iterate_all_by_sh(genORM::database& __db, const std::optional<Shape> sh) {
+    static constexpr std::string_view select_statement = "SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE sh IS ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return sh ? genORM::value_variant{Shape_to_value(*sh)} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        return genORM::object_range<MyThirdObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_all_by_ts(genORM::database& __db, const std::chrono::sys_time<std::chrono::microseconds> ts) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE ts = ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(ts.time_since_epoch().count()); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        std::vector<MyThirdObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::iterate_all_by_ts(genORM::database& __db, const std::chrono::sys_time<std::chrono::microseconds> ts) {
+    static constexpr std::string_view select_statement = "SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE ts = ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(ts.time_since_epoch().count()); },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        return genORM::object_range<MyThirdObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_all_by_tt(genORM::database& __db, const std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE tt IS ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return tt ? genORM::value_variant{genORM::format_timestamp((*tt), 3)} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        std::vector<MyThirdObject> found_objects;
+        if (auto& rows = *select_result; not rows.empty()) {
+            for (auto& row : rows) {
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::iterate_all_by_tt(genORM::database& __db, const std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt) {
+    static constexpr std::string_view select_statement = "SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE tt IS ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return tt ? genORM::value_variant{genORM::format_timestamp((*tt), 3)} : std::monostate{}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        return genORM::object_range<MyThirdObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_by_c_and_sh(genORM::database& __db, const Color c, const std::optional<Shape> sh) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE c = ? AND sh IS ? LIMIT 1;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return Color_to_value(c); }
+        if (value_index == 2) { return sh ? genORM::value_variant{Shape_to_value(*sh)} : std::monostate{}; }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto select_result = select_one(__db, select_statement, 2, binder,
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_all_by_ts_between(genORM::database& __db, const std::chrono::sys_time<std::chrono::microseconds> __from, const std::chrono::sys_time<std::chrono::microseconds> __to) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE ts BETWEEN ? AND ?;"};
+    if (auto select_result = select_all(__db, select_statement, 2, [=](int value_index) -> genORM::value_variant { return value_index == 1 ? genORM::value_variant{static_cast<int64_t>(__from.time_since_epoch().count())} : genORM::value_variant{static_cast<int64_t>(__to.time_since_epoch().count())}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        std::vector<MyThirdObject> found_objects;
+        for (auto& row : *select_result) {
+            if (auto found_object = from_row(__db, row)) {
+                found_objects.emplace_back(std::move(*found_object));
+            } else {
+                return std::unexpected{std::move(found_object.error())};
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<std::vector<testproj::MyThirdObject>, genORM::error> testproj::MyThirdObject::find_all_by_tt_between(genORM::database& __db, const std::chrono::sys_time<std::chrono::milliseconds> __from, const std::chrono::sys_time<std::chrono::milliseconds> __to) {
+    static const genORM::cached_statement select_statement{"SELECT __id, c, sh, ts, tt FROM MyThirdObject WHERE tt BETWEEN ? AND ?;"};
+    if (auto select_result = select_all(__db, select_statement, 2, [=](int value_index) -> genORM::value_variant { return value_index == 1 ? genORM::value_variant{genORM::format_timestamp(__from, 3)} : genORM::value_variant{genORM::format_timestamp(__to, 3)}; },
+            std::vector<genORM::value_variant>{{int64_t{}, int32_t{}, std::string{}, int64_t{}, std::string{}}})) {
+        std::vector<MyThirdObject> found_objects;
+        for (auto& row : *select_result) {
+            if (auto found_object = from_row(__db, row)) {
+                found_objects.emplace_back(std::move(*found_object));
+            } else {
+                return std::unexpected{std::move(found_object.error())};
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<uint64_t, genORM::error> testproj::MyThirdObject::delete_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyThirdObject WHERE __id = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); });
+}
+std::expected<uint64_t, genORM::error> testproj::MyThirdObject::delete_all_by_c(genORM::database& __db, const Color c) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyThirdObject WHERE c = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return Color_to_value(c); });
+}
+std::expected<uint64_t, genORM::error> testproj::MyThirdObject::delete_all_by_sh(genORM::database& __db, const std::optional<Shape> sh) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyThirdObject WHERE sh IS ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return sh ? genORM::value_variant{Shape_to_value(*sh)} : std::monostate{}; });
+}
+std::expected<uint64_t, genORM::error> testproj::MyThirdObject::delete_all_by_ts(genORM::database& __db, const std::chrono::sys_time<std::chrono::microseconds> ts) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyThirdObject WHERE ts = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(ts.time_since_epoch().count()); });
+}
+std::expected<uint64_t, genORM::error> testproj::MyThirdObject::delete_all_by_tt(genORM::database& __db, const std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyThirdObject WHERE tt IS ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return tt ? genORM::value_variant{genORM::format_timestamp((*tt), 3)} : std::monostate{}; });
+}
+std::expected<void, genORM::error> testproj::MyThirdObject::save() {
+    static const genORM::cached_statement update_statement{"UPDATE MyThirdObject SET c = CASE WHEN ? THEN ? ELSE c END, sh = CASE WHEN ? THEN ? ELSE sh END, ts = CASE WHEN ? THEN ? ELSE ts END, tt = CASE WHEN ? THEN ? ELSE tt END WHERE __id = ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(_changed.test(0)); }
+        if (value_index == 2) { return Color_to_value(_c); }
+        if (value_index == 3) { return static_cast<int64_t>(_changed.test(1)); }
+        if (value_index == 4) { return _sh ? genORM::value_variant{Shape_to_value(*_sh)} : std::monostate{}; }
+        if (value_index == 5) { return static_cast<int64_t>(_changed.test(2)); }
+        if (value_index == 6) { return static_cast<int64_t>(_ts.time_since_epoch().count()); }
+        if (value_index == 7) { return static_cast<int64_t>(_changed.test(3)); }
+        if (value_index == 8) { return _tt ? genORM::value_variant{genORM::format_timestamp((*_tt), 3)} : std::monostate{}; }
+        if (value_index == 9) { return static_cast<int64_t>(get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto update_result = update_table(get_database(), update_statement, 9, binder); not update_result) {
+        return std::unexpected{std::move(update_result.error())};
+    } else if (*update_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    _changed.reset();
+    return {};
+}
+std::expected<void, genORM::error> testproj::MyThirdObject::remove() const {
+    if (auto delete_result = delete_by_rowid(get_database(), get_rowid()); not delete_result) {
+        return std::unexpected{std::move(delete_result.error())};
+    } else if (*delete_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    return {};
+}
+testproj::MyFourthObject::MyFourthObject(genORM::database& __db, uint64_t __id, uint64_t o, std::optional<uint64_t> so)
+    : object(__db, __id), _o(o), _so(so) {}
+
+std::expected<testproj::MyFourthObject, genORM::error> testproj::MyFourthObject::from_row(genORM::database& __db, std::vector<genORM::value_variant>& row) {
+    return MyFourthObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
+        static_cast<uint64_t>(std::get<int64_t>(row[1])),
+        std::holds_alternative<int64_t>(row[2]) ? static_cast<uint64_t>(std::get<int64_t>(row[2])) : std::optional<uint64_t>{}
+    };
+}
+std::expected<void, genORM::error> testproj::MyFourthObject::ensure_schema(genORM::database& __db) {
+    static constexpr std::string_view create_table_statement = "CREATE TABLE IF NOT EXISTS MyFourthObject (__id INTEGER PRIMARY KEY NOT NULL, o INTEGER NOT NULL REFERENCES MyObject(__id) ON DELETE CASCADE, so INTEGER DEFAULT NULL REFERENCES MySecondObject(__id) ON DELETE SET NULL) STRICT;";
+    if (auto create_table_result = create_table_if_not_exists(__db, create_table_statement); not create_table_result) { return std::unexpected{std::move(create_table_result.error())}; }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyFourthObject_o ON MyFourthObject(o);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    return {};
+}
+std::expected<testproj::MyFourthObject, genORM::error> testproj::MyFourthObject::create(genORM::database& __db, uint64_t o, std::optional<uint64_t> so) {
+    static const genORM::cached_statement insert_statement{"INSERT INTO MyFourthObject (o, so) VALUES (?, ?);"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(o); }
+        if (value_index == 2) { return so ? genORM::value_variant{static_cast<int64_t>(*so)} : std::monostate{}; }
         throw std::logic_error("Implementation error");
     };
     if (auto create_table_result = insert_into_table(__db, insert_statement, 2, binder)) {
-        return MySecondObject{__db, *create_table_result, l, lo};
+        return MyFourthObject{__db, *create_table_result, o, so};
     } else {
         return std::unexpected{std::move(create_table_result.error())};
     }
 }
-std::expected<std::optional<testproj::MySecondObject>, std::string> testproj::MySecondObject::find_by_rowid(genORM::database& __db, const uint64_t __id) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MySecondObject WHERE __id = ? LIMIT 1;";
+std::expected<std::optional<testproj::MyFourthObject>, genORM::error> testproj::MyFourthObject::find_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement select_statement{"SELECT __id, o, so FROM MyFourthObject WHERE __id = ? LIMIT 1;"};
     if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); },
             std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}}})) {
         if (*select_result) {
-            return MySecondObject{__db, __id,
-                std::get<int64_t>((**select_result)[1]),
-                std::holds_alternative<int64_t>((**select_result)[2]) ? std::get<int64_t>((**select_result)[2]) : std::optional<int64_t>{}
-            };
+            return from_row(__db, **select_result);
         } else {
             return std::nullopt;
         }
@@ -155,15 +1006,12 @@ std::expected<std::optional<testproj::MySecondObject>, std::string> testproj::My
         return std::unexpected{std::move(select_result.error())};
     }
 }
-std::expected<std::optional<testproj::MySecondObject>, std::string> testproj::MySecondObject::find_first_by_lo(genORM::database& __db, const std::optional<int64_t> lo) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MySecondObject WHERE lo = ? LIMIT 1;";
-    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return lo ? genORM::value_variant{*lo} : std::monostate{}; },
+std::expected<std::optional<testproj::MyFourthObject>, genORM::error> testproj::MyFourthObject::find_first_by_o(genORM::database& __db, const uint64_t o) {
+    static const genORM::cached_statement select_statement{"SELECT __id, o, so FROM MyFourthObject WHERE o = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(o); },
             std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}}})) {
         if (*select_result) {
-            return MySecondObject{__db, static_cast<uint64_t>(std::get<int64_t>((**select_result)[0])),
-                std::get<int64_t>((**select_result)[1]),
-                std::holds_alternative<int64_t>((**select_result)[2]) ? std::get<int64_t>((**select_result)[2]) : std::optional<int64_t>{}
-            };
+            return from_row(__db, **select_result);
         } else {
             return std::nullopt;
         }
@@ -171,17 +1019,191 @@ std::expected<std::optional<testproj::MySecondObject>, std::string> testproj::My
         return std::unexpected{std::move(select_result.error())};
     }
 }
-std::expected<std::vector<testproj::MySecondObject>, std::string> testproj::MySecondObject::find_all_by_lo(genORM::database& __db, const std::optional<int64_t> lo) {
-    static constexpr std::string_view select_statement = "SELECT * FROM MySecondObject WHERE lo = ?;";
-    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return lo ? genORM::value_variant{*lo} : std::monostate{}; },
+std::expected<std::vector<testproj::MyFourthObject>, genORM::error> testproj::MyFourthObject::find_all_by_o(genORM::database& __db, const uint64_t o) {
+    static const genORM::cached_statement select_statement{"SELECT __id, o, so FROM MyFourthObject WHERE o = ?;"};
+    if (auto select_result = select_all(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(o); },
             std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}}})) {
-        std::vector<MySecondObject> found_objects;
+        std::vector<MyFourthObject> found_objects;
         if (auto& rows = *select_result; not rows.empty()) {
             for (auto& row : rows) {
-                found_objects.emplace_back(MySecondObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
-                    std::get<int64_t>(row[1]),
-                    std::holds_alternative<int64_t>(row[2]) ? std::get<int64_t>(row[2]) : std::optional<int64_t>{}
-                });
+                if (auto found_object = from_row(__db, row)) {
+                    found_objects.emplace_back(std::move(*found_object));
+                } else {
+                    return std::unexpected{std::move(found_object.error())};
+                }
+            }
+        }
+        return found_objects;
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<genORM::object_range<testproj::MyFourthObject>, genORM::error> testproj::MyFourthObject::iterate_all_by_o(genORM::database& __db, const uint64_t o) {
+    static constexpr std::string_view select_statement = "SELECT __id, o, so FROM MyFourthObject WHERE o = ?;";
+    if (auto select_result = select_cursor(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(o); },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, int64_t{}}})) {
+        return genORM::object_range<MyFourthObject>{std::move(*select_result), [&__db](std::vector<genORM::value_variant>& row) { return from_row(__db, row); }};
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<uint64_t, genORM::error> testproj::MyFourthObject::delete_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyFourthObject WHERE __id = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); });
+}
+std::expected<uint64_t, genORM::error> testproj::MyFourthObject::delete_all_by_o(genORM::database& __db, const uint64_t o) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyFourthObject WHERE o = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(o); });
+}
+std::expected<void, genORM::error> testproj::MyFourthObject::save() {
+    static const genORM::cached_statement update_statement{"UPDATE MyFourthObject SET o = CASE WHEN ? THEN ? ELSE o END, so = CASE WHEN ? THEN ? ELSE so END WHERE __id = ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(_changed.test(0)); }
+        if (value_index == 2) { return static_cast<int64_t>(_o); }
+        if (value_index == 3) { return static_cast<int64_t>(_changed.test(1)); }
+        if (value_index == 4) { return _so ? genORM::value_variant{static_cast<int64_t>(*_so)} : std::monostate{}; }
+        if (value_index == 5) { return static_cast<int64_t>(get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto update_result = update_table(get_database(), update_statement, 5, binder); not update_result) {
+        return std::unexpected{std::move(update_result.error())};
+    } else if (*update_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    _changed.reset();
+    return {};
+}
+std::expected<void, genORM::error> testproj::MyFourthObject::remove() const {
+    if (auto delete_result = delete_by_rowid(get_database(), get_rowid()); not delete_result) {
+        return std::unexpected{std::move(delete_result.error())};
+    } else if (*delete_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    return {};
+}
+std::expected<std::optional<testproj::MyObject>, genORM::error> testproj::MyFourthObject::get_o_object() const {
+    return MyObject::find_by_rowid(get_database(), _o);
+}
+std::expected<std::optional<testproj::MySecondObject>, genORM::error> testproj::MyFourthObject::get_so_object() const {
+    if (not _so) { return std::nullopt; }
+    return MySecondObject::find_by_rowid(get_database(), *_so);
+}
+testproj::MyFifthObject::MyFifthObject(genORM::database& __db, uint64_t __id, uint64_t owner, std::string title)
+    : object(__db, __id), _owner(owner), _title(std::move(title)) {}
+
+std::expected<testproj::MyFifthObject, genORM::error> testproj::MyFifthObject::from_row(genORM::database& __db, std::vector<genORM::value_variant>& row) {
+    return MyFifthObject{__db, static_cast<uint64_t>(std::get<int64_t>(row[0])),
+        static_cast<uint64_t>(std::get<int64_t>(row[1])),
+        std::move(std::get<std::string>(row[2]))
+    };
+}
+std::expected<void, genORM::error> testproj::MyFifthObject::ensure_schema(genORM::database& __db) {
+    static constexpr std::string_view create_table_statement = "CREATE TABLE IF NOT EXISTS MyFifthObject (__id INTEGER PRIMARY KEY NOT NULL, owner INTEGER NOT NULL REFERENCES MySecondObject(__id) ON DELETE CASCADE, title TEXT NOT NULL DEFAULT '') STRICT;";
+    if (auto create_table_result = create_table_if_not_exists(__db, create_table_statement); not create_table_result) { return std::unexpected{std::move(create_table_result.error())}; }
+    {
+        static constexpr std::string_view create_index_statement = "CREATE UNIQUE INDEX IF NOT EXISTS UniqueIndex_MyFifthObject_owner ON MyFifthObject(owner);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    {
+        static constexpr std::string_view create_join_table_statement = "CREATE TABLE IF NOT EXISTS MyFifthObject_links (__owner_id INTEGER NOT NULL REFERENCES MyFifthObject(__id) ON DELETE CASCADE, __target_id INTEGER NOT NULL REFERENCES MyFifthObject(__id) ON DELETE CASCADE, PRIMARY KEY (__owner_id, __target_id)) STRICT;";
+        if (auto create_table_result = create_table_if_not_exists(__db, create_join_table_statement); not create_table_result) { return std::unexpected{std::move(create_table_result.error())}; }
+        static constexpr std::string_view create_index_statement = "CREATE INDEX IF NOT EXISTS Index_MyFifthObject_links___target_id ON MyFifthObject_links(__target_id);";
+        if (auto create_index_result = create_index_if_not_exists(__db, create_index_statement); not create_index_result) { return std::unexpected{std::move(create_index_result.error())}; }
+    }
+    return {};
+}
+std::expected<testproj::MyFifthObject, genORM::error> testproj::MyFifthObject::create(genORM::database& __db, uint64_t owner, std::string title) {
+    static const genORM::cached_statement insert_statement{"INSERT INTO MyFifthObject (owner, title) VALUES (?, ?);"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(owner); }
+        if (value_index == 2) { return title; }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto create_table_result = insert_into_table(__db, insert_statement, 2, binder)) {
+        return MyFifthObject{__db, *create_table_result, owner, std::move(title)};
+    } else {
+        return std::unexpected{std::move(create_table_result.error())};
+    }
+}
+std::expected<std::optional<testproj::MyFifthObject>, genORM::error> testproj::MyFifthObject::find_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement select_statement{"SELECT __id, owner, title FROM MyFifthObject WHERE __id = ? LIMIT 1;"};
+    if (auto select_result = select_one(__db, select_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+
+
+std::expected<std::optional<testproj::MyFifthObject>, genORM::error> testproj::MyFifthObject::find_by_owner(genORM::database& __db, const uint64_t owner) {
+    static const genORM::cached_statement select_statement{"SELECT __id, owner, title FROM MyFifthObject WHERE owner = ? LIMIT 1;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(owner); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto select_result = select_one(__db, select_statement, 1, binder,
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, std::string{}}})) {
+        if (*select_result) {
+            return from_row(__db, **select_result);
+        } else {
+            return std::nullopt;
+        }
+    } else {
+        return std::unexpected{std::move(select_result.error())};
+    }
+}
+std::expected<uint64_t, genORM::error> testproj::MyFifthObject::delete_by_rowid(genORM::database& __db, const uint64_t __id) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyFifthObject WHERE __id = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(__id); });
+}
+std::expected<uint64_t, genORM::error> testproj::MyFifthObject::delete_all_by_owner(genORM::database& __db, const uint64_t owner) {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyFifthObject WHERE owner = ?;"};
+    return delete_from_table(__db, delete_statement, 1, [=](int) -> genORM::value_variant { return static_cast<int64_t>(owner); });
+}
+std::expected<void, genORM::error> testproj::MyFifthObject::save() {
+    static const genORM::cached_statement update_statement{"UPDATE MyFifthObject SET owner = CASE WHEN ? THEN ? ELSE owner END, title = CASE WHEN ? THEN ? ELSE title END WHERE __id = ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(_changed.test(0)); }
+        if (value_index == 2) { return static_cast<int64_t>(_owner); }
+        if (value_index == 3) { return static_cast<int64_t>(_changed.test(1)); }
+        if (value_index == 4) { return _title; }
+        if (value_index == 5) { return static_cast<int64_t>(get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto update_result = update_table(get_database(), update_statement, 5, binder); not update_result) {
+        return std::unexpected{std::move(update_result.error())};
+    } else if (*update_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    _changed.reset();
+    return {};
+}
+std::expected<void, genORM::error> testproj::MyFifthObject::remove() const {
+    if (auto delete_result = delete_by_rowid(get_database(), get_rowid()); not delete_result) {
+        return std::unexpected{std::move(delete_result.error())};
+    } else if (*delete_result == 0) {
+        return std::unexpected{genORM::error{genORM::error_category::not_found, "Object no longer exists in the database"}};
+    }
+    return {};
+}
+std::expected<std::optional<testproj::MySecondObject>, genORM::error> testproj::MyFifthObject::get_owner_object() const {
+    return MySecondObject::find_by_rowid(get_database(), _owner);
+}
+std::expected<std::vector<testproj::MyFifthObject>, genORM::error> testproj::MyFifthObject::get_links() const {
+    static const genORM::cached_statement select_statement{"SELECT MyFifthObject.__id, MyFifthObject.owner, MyFifthObject.title FROM MyFifthObject JOIN MyFifthObject_links ON MyFifthObject.__id = MyFifthObject_links.__target_id WHERE MyFifthObject_links.__owner_id = ?;"};
+    if (auto select_result = select_all(get_database(), select_statement, 1, [this](int) -> genORM::value_variant { return static_cast<int64_t>(get_rowid()); },
+            std::vector<genORM::value_variant>{{int64_t{}, int64_t{}, std::string{}}})) {
+        std::vector<MyFifthObject> found_objects;
+        for (auto& row : *select_result) {
+            if (auto found_object = MyFifthObject::from_row(get_database(), row)) {
+                found_objects.emplace_back(std::move(*found_object));
+            } else {
+                return std::unexpected{std::move(found_object.error())};
             }
         }
         return found_objects;
@@ -189,3 +1211,156 @@ std::expected<std::vector<testproj::MySecondObject>, std::string> testproj::MySe
         return std::unexpected{std::move(select_result.error())};
     }
 }
+std::expected<void, genORM::error> testproj::MyFifthObject::link_links(const MyFifthObject& other) const {
+    // Linking twice is not an error, the pair is only stored once
+    static const genORM::cached_statement insert_statement{"INSERT OR IGNORE INTO MyFifthObject_links VALUES (?, ?);"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(get_rowid()); }
+        if (value_index == 2) { return static_cast<int64_t>(other.get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto insert_result = insert_into_table(get_database(), insert_statement, 2, binder); not insert_result) {
+        return std::unexpected{std::move(insert_result.error())};
+    }
+    return {};
+}
+std::expected<void, genORM::error> testproj::MyFifthObject::unlink_links(const MyFifthObject& other) const {
+    static const genORM::cached_statement delete_statement{"DELETE FROM MyFifthObject_links WHERE __owner_id = ? AND __target_id = ?;"};
+    const auto binder = [&](int value_index) -> genORM::value_variant {
+        if (value_index == 1) { return static_cast<int64_t>(get_rowid()); }
+        if (value_index == 2) { return static_cast<int64_t>(other.get_rowid()); }
+        throw std::logic_error("Implementation error");
+    };
+    if (auto delete_result = delete_from_table(get_database(), delete_statement, 2, binder); not delete_result) {
+        return std::unexpected{std::move(delete_result.error())};
+    }
+    return {};
+}
+std::expected<void, genORM::error> testproj::initialize_schema(genORM::database& __db) {
+    return __db.execute_transaction<void>([](genORM::database& __db) -> std::expected<void, genORM::error> {
+        if (auto ensure_result = MyObject::ensure_schema(__db); not ensure_result) { return ensure_result; }
+        if (auto ensure_result = MySecondObject::ensure_schema(__db); not ensure_result) { return ensure_result; }
+        if (auto ensure_result = MyThirdObject::ensure_schema(__db); not ensure_result) { return ensure_result; }
+        if (auto ensure_result = MyFourthObject::ensure_schema(__db); not ensure_result) { return ensure_result; }
+        if (auto ensure_result = MyFifthObject::ensure_schema(__db); not ensure_result) { return ensure_result; }
+        return {};
+    }, genORM::transaction_mode::immediate);
+}
+std::expected<void, genORM::error> testproj::migrate(genORM::database& __db) {
+    const auto user_version = __db.get_user_version();
+    if (not user_version) { return std::unexpected{std::move(user_version.error())}; }
+    if (schema_version < *user_version) { return std::unexpected{genORM::error{genORM::error_category::schema_mismatch, "Database schema version " + std::to_string(*user_version) + " is newer than the generated code"}}; }
+    if (*user_version == schema_version) { return {}; }
+    // A database without any of the tables is new, the tables will be created with the latest schema
+    if (*user_version == 0) {
+        bool is_new = true;
+        for (const std::string_view table : {"MyObject", "MySecondObject", "MyThirdObject", "MyFourthObject", "MyFifthObject"}) {
+            if (auto has_table = __db.has_table(table); not has_table) { return std::unexpected{std::move(has_table.error())}; } else if (*has_table) { is_new = false; }
+        }
+        if (is_new) { return __db.set_user_version(schema_version); }
+    }
+    if (__db.is_in_transaction()) { return std::unexpected{genORM::error{genORM::error_category::misuse, "Migrations cannot run inside a transaction"}}; }
+    // Rebuilt tables are dropped, which must not cascade into the tables referencing them
+    const auto foreign_keys = __db.get_foreign_keys();
+    if (not foreign_keys) { return std::unexpected{std::move(foreign_keys.error())}; }
+    if (auto pragma_result = __db.set_foreign_keys(false); not pragma_result) { return std::unexpected{std::move(pragma_result.error())}; }
+    auto migrate_result = __db.execute_transaction<void>([&](genORM::database& __db) -> std::expected<void, genORM::error> {
+        if (*user_version < 1) {
+            // Add the optional integer, starting out as a copy of the integer
+            if (auto has_table = __db.has_table("MyObject"); not has_table) {
+                return std::unexpected{std::move(has_table.error())};
+            } else if (auto has_column = __db.has_column("MyObject", "io"); not has_column) {
+                return std::unexpected{std::move(has_column.error())};
+            } else if (*has_table and not *has_column) {
+                if (auto alter_result = __db.execute("ALTER TABLE MyObject ADD COLUMN io INTEGER  DEFAULT NULL;"); not alter_result) { return std::unexpected{std::move(alter_result.error())}; }
+            }
+            if (auto sql_result = __db.execute("UPDATE MyObject SET io = i WHERE io IS NULL;"); not sql_result) { return std::unexpected{std::move(sql_result.error())}; }
+        }
+        if (*user_version < 2) {
+            if (auto has_column = __db.has_column("MySecondObject", "str"); not has_column) {
+                return std::unexpected{std::move(has_column.error())};
+            } else if (*has_column) {
+                if (auto alter_result = __db.execute("ALTER TABLE MySecondObject RENAME COLUMN str TO s;"); not alter_result) { return std::unexpected{std::move(alter_result.error())}; }
+            }
+        }
+        if (*user_version < 3) {
+            // Drop the legacy column
+            if (auto has_table = __db.has_table("MyThirdObject"); not has_table) {
+                return std::unexpected{std::move(has_table.error())};
+            } else if (*has_table) {
+                if (auto create_result = __db.execute("CREATE TABLE IF NOT EXISTS MyThirdObject__new (__id INTEGER PRIMARY KEY NOT NULL, c INTEGER NOT NULL DEFAULT 0, sh TEXT  DEFAULT NULL, ts INTEGER NOT NULL DEFAULT 0, tt TEXT  DEFAULT NULL) STRICT;"); not create_result) { return std::unexpected{std::move(create_result.error())}; }
+                // Members missing from the old table are filled with their defaults
+                std::string columns = "__id";
+                for (const std::string_view column : {"c", "sh", "ts", "tt"}) {
+                    if (auto has_column = __db.has_column("MyThirdObject", column); not has_column) { return std::unexpected{std::move(has_column.error())}; } else if (*has_column) { columns += ", " + std::string{column}; }
+                }
+                if (auto copy_result = __db.execute("INSERT INTO MyThirdObject__new (" + columns + ") SELECT " + columns + " FROM MyThirdObject;"); not copy_result) { return std::unexpected{std::move(copy_result.error())}; }
+                if (auto drop_result = __db.execute("DROP TABLE MyThirdObject;"); not drop_result) { return std::unexpected{std::move(drop_result.error())}; }
+                if (auto rename_result = __db.execute("ALTER TABLE MyThirdObject__new RENAME TO MyThirdObject;"); not rename_result) { return std::unexpected{std::move(rename_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyThirdObject_c ON MyThirdObject(c);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyThirdObject_sh ON MyThirdObject(sh);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyThirdObject_ts ON MyThirdObject(ts);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyThirdObject_tt ON MyThirdObject(tt);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto index_result = __db.execute("CREATE UNIQUE INDEX IF NOT EXISTS UniqueIndex_MyThirdObject_c_sh ON MyThirdObject(c, sh);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto check_result = __db.check_foreign_keys("MyThirdObject"); not check_result) { return std::unexpected{std::move(check_result.error())}; }
+            }
+        }
+        if (*user_version < 4) {
+            if (auto has_table = __db.has_table("MyFifthObject"); not has_table) {
+                return std::unexpected{std::move(has_table.error())};
+            } else if (auto has_column = __db.has_column("MyFifthObject", "label"); not has_column) {
+                return std::unexpected{std::move(has_column.error())};
+            } else if (*has_table and not *has_column) {
+                if (auto alter_result = __db.execute("ALTER TABLE MyFifthObject ADD COLUMN label TEXT NOT NULL DEFAULT 'untitled';"); not alter_result) { return std::unexpected{std::move(alter_result.error())}; }
+            }
+        }
+        if (*user_version < 5) {
+            // Rename the label, rebuilding the table to drop the default of the old column
+            if (auto has_column = __db.has_column("MyFifthObject", "label"); not has_column) {
+                return std::unexpected{std::move(has_column.error())};
+            } else if (*has_column) {
+                if (auto alter_result = __db.execute("ALTER TABLE MyFifthObject RENAME COLUMN label TO title;"); not alter_result) { return std::unexpected{std::move(alter_result.error())}; }
+            }
+            if (auto has_table = __db.has_table("MyFifthObject"); not has_table) {
+                return std::unexpected{std::move(has_table.error())};
+            } else if (*has_table) {
+                if (auto create_result = __db.execute("CREATE TABLE IF NOT EXISTS MyFifthObject__new (__id INTEGER PRIMARY KEY NOT NULL, owner INTEGER NOT NULL REFERENCES MySecondObject(__id) ON DELETE CASCADE, title TEXT NOT NULL DEFAULT '') STRICT;"); not create_result) { return std::unexpected{std::move(create_result.error())}; }
+                // Members missing from the old table are filled with their defaults
+                std::string columns = "__id";
+                for (const std::string_view column : {"owner", "title"}) {
+                    if (auto has_column = __db.has_column("MyFifthObject", column); not has_column) { return std::unexpected{std::move(has_column.error())}; } else if (*has_column) { columns += ", " + std::string{column}; }
+                }
+                if (auto copy_result = __db.execute("INSERT INTO MyFifthObject__new (" + columns + ") SELECT " + columns + " FROM MyFifthObject;"); not copy_result) { return std::unexpected{std::move(copy_result.error())}; }
+                if (auto drop_result = __db.execute("DROP TABLE MyFifthObject;"); not drop_result) { return std::unexpected{std::move(drop_result.error())}; }
+                if (auto rename_result = __db.execute("ALTER TABLE MyFifthObject__new RENAME TO MyFifthObject;"); not rename_result) { return std::unexpected{std::move(rename_result.error())}; }
+                if (auto index_result = __db.execute("CREATE UNIQUE INDEX IF NOT EXISTS UniqueIndex_MyFifthObject_owner ON MyFifthObject(owner);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto check_result = __db.check_foreign_keys("MyFifthObject"); not check_result) { return std::unexpected{std::move(check_result.error())}; }
+            }
+        }
+        if (*user_version < 6) {
+            // Byte arrays that do not allow null used to be created as nullable BLOB columns, fill in the empty ones and rebuild the table to make the column NOT NULL
+            if (auto sql_result = __db.execute("UPDATE MyObject SET ba = x'' WHERE ba IS NULL;"); not sql_result) { return std::unexpected{std::move(sql_result.error())}; }
+            if (auto has_table = __db.has_table("MyObject"); not has_table) {
+                return std::unexpected{std::move(has_table.error())};
+            } else if (*has_table) {
+                if (auto create_result = __db.execute("CREATE TABLE IF NOT EXISTS MyObject__new (__id INTEGER PRIMARY KEY NOT NULL, i INTEGER NOT NULL DEFAULT 0, io INTEGER  DEFAULT NULL, ba BLOB NOT NULL DEFAULT x'') STRICT;"); not create_result) { return std::unexpected{std::move(create_result.error())}; }
+                // Members missing from the old table are filled with their defaults
+                std::string columns = "__id";
+                for (const std::string_view column : {"i", "io", "ba"}) {
+                    if (auto has_column = __db.has_column("MyObject", column); not has_column) { return std::unexpected{std::move(has_column.error())}; } else if (*has_column) { columns += ", " + std::string{column}; }
+                }
+                if (auto copy_result = __db.execute("INSERT INTO MyObject__new (" + columns + ") SELECT " + columns + " FROM MyObject;"); not copy_result) { return std::unexpected{std::move(copy_result.error())}; }
+                if (auto drop_result = __db.execute("DROP TABLE MyObject;"); not drop_result) { return std::unexpected{std::move(drop_result.error())}; }
+                if (auto rename_result = __db.execute("ALTER TABLE MyObject__new RENAME TO MyObject;"); not rename_result) { return std::unexpected{std::move(rename_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyObject_i ON MyObject(i);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyObject_io ON MyObject(io);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyObject_ba ON MyObject(ba);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto index_result = __db.execute("CREATE INDEX IF NOT EXISTS Index_MyObject_i_io ON MyObject(i, io);"); not index_result) { return std::unexpected{std::move(index_result.error())}; }
+                if (auto check_result = __db.check_foreign_keys("MyObject"); not check_result) { return std::unexpected{std::move(check_result.error())}; }
+            }
+        }
+        return __db.set_user_version(schema_version);
+    }, genORM::transaction_mode::immediate);
+    if (auto pragma_result = __db.set_foreign_keys(*foreign_keys); not pragma_result) { return std::unexpected{std::move(pragma_result.error())}; }
+    return migrate_result;
+}
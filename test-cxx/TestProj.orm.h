@@ -4,8 +4,32 @@
 #include <vector>
 #include <string>
 #include <optional>
+#include <chrono>
+#include <bitset>
 
 namespace testproj {
+    /// This is some enum stored as an integer
+    enum class Color {
+        RED,
+        GREEN,
+        BLUE,
+    };
+    genORM::value_variant Color_to_value(Color);
+    std::expected<Color, genORM::error> Color_from_value(const genORM::value_variant&);
+
+    enum class Shape {
+        CIRCLE,
+        SQUARE,
+    };
+    genORM::value_variant Shape_to_value(Shape);
+    std::expected<Shape, genORM::error> Shape_from_value(const genORM::value_variant&);
+
+    class MyObject;
+    class MySecondObject;
+    class MyThirdObject;
+    class MyFourthObject;
+    class MyFifthObject;
+
     /// This is an object that's created for test purposes.
     class MyObject final : public genORM::object {
         /// This is some integer
@@ -16,34 +40,254 @@ namespace testproj {
         std::vector<uint8_t> _ba;
 
         explicit MyObject(genORM::database& __db, uint64_t __id, int32_t i, std::optional<int32_t> io, std::vector<uint8_t> ba);
+        static std::expected<MyObject, genORM::error> from_row(genORM::database& __db, std::vector<genORM::value_variant>& row);
 
     public:
-        static std::expected<MyObject, std::string> create(genORM::database& __db, int32_t i, std::optional<int32_t> io, std::vector<uint8_t> ba);
-        static std::expected<std::optional<MyObject>, std::string> find_by_rowid(genORM::database& __db, uint64_t);
-        static std::expected<std::optional<MyObject>, std::string> find_first_by_i(genORM::database& __db, int32_t i);
-        static std::expected<std::vector<MyObject>, std::string> find_all_by_i(genORM::database& __db, int32_t i);
-        static std::expected<std::optional<MyObject>, std::string> find_first_by_io(genORM::database& __db, std::optional<int32_t> io);
-        static std::expected<std::vector<MyObject>, std::string> find_all_by_io(genORM::database& __db, std::optional<int32_t> io);
+        /// Creates the table and indexes of the type, unless they already exist.
+        static std::expected<void, genORM::error> ensure_schema(genORM::database& __db);
+        static std::expected<MyObject, genORM::error> create(genORM::database& __db, int32_t i, std::optional<int32_t> io, std::vector<uint8_t> ba);
+        static std::expected<std::optional<MyObject>, genORM::error> find_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<std::optional<MyObject>, genORM::error> find_first_by_i(genORM::database& __db, int32_t i);
+        static std::expected<std::vector<MyObject>, genORM::error> find_all_by_i(genORM::database& __db, int32_t i);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyObject>, genORM::error> iterate_all_by_i(genORM::database& __db, int32_t i);
+        static std::expected<std::optional<MyObject>, genORM::error> find_first_by_io(genORM::database& __db, std::optional<int32_t> io);
+        static std::expected<std::vector<MyObject>, genORM::error> find_all_by_io(genORM::database& __db, std::optional<int32_t> io);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyObject>, genORM::error> iterate_all_by_io(genORM::database& __db, std::optional<int32_t> io);
+        static std::expected<std::optional<MyObject>, genORM::error> find_first_by_ba(genORM::database& __db, std::vector<uint8_t> ba);
+        static std::expected<std::vector<MyObject>, genORM::error> find_all_by_ba(genORM::database& __db, std::vector<uint8_t> ba);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyObject>, genORM::error> iterate_all_by_ba(genORM::database& __db, std::vector<uint8_t> ba);
+        static std::expected<std::optional<MyObject>, genORM::error> find_first_by_i_and_io(genORM::database& __db, int32_t i, std::optional<int32_t> io);
+        static std::expected<std::vector<MyObject>, genORM::error> find_all_by_i_and_io(genORM::database& __db, int32_t i, std::optional<int32_t> io);
+        static std::expected<uint64_t, genORM::error> delete_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<uint64_t, genORM::error> delete_all_by_i(genORM::database& __db, int32_t i);
+        static std::expected<uint64_t, genORM::error> delete_all_by_io(genORM::database& __db, std::optional<int32_t> io);
+        static std::expected<uint64_t, genORM::error> delete_all_by_ba(genORM::database& __db, std::vector<uint8_t> ba);
+        /// Writes the members changed through the setters since the object was read or last saved, other columns keep
+        /// the values they have in the database.
+        std::expected<void, genORM::error> save();
+        std::expected<void, genORM::error> remove() const;
+        /// Objects referencing this object
+        std::expected<std::vector<MyFourthObject>, genORM::error> get_children() const;
+        std::expected<std::vector<MyThirdObject>, genORM::error> get_tags() const;
+        std::expected<void, genORM::error> link_tags(const MyThirdObject& other) const;
+        std::expected<void, genORM::error> unlink_tags(const MyThirdObject& other) const;
 
         [[nodiscard]] int32_t get_i() const { return _i; }
         [[nodiscard]] std::optional<int32_t> get_io() const { return _io; }
         [[nodiscard]] const std::vector<uint8_t>& get_ba() const { return _ba; }
+        void set_i(int32_t i) { _i = i; _changed.set(0); }
+        void set_io(std::optional<int32_t> io) { _io = io; _changed.set(1); }
+        void set_ba(std::vector<uint8_t> ba) { _ba = std::move(ba); _changed.set(2); }
+    private:
+        std::bitset<3> _changed;
     };
 
     class MySecondObject final : public genORM::object {
         int64_t _l;
         std::optional<int64_t> _lo;
+        std::string _s;
+        std::optional<std::string> _so;
+        float _f;
+        std::optional<double> _d;
+        bool _b;
+        std::optional<std::vector<uint8_t>> _bo;
 
-        explicit MySecondObject(genORM::database& __db, uint64_t __id, int64_t l, std::optional<int64_t> lo);
+        explicit MySecondObject(genORM::database& __db, uint64_t __id, int64_t l, std::optional<int64_t> lo, std::string s, std::optional<std::string> so, float f, std::optional<double> d, bool b, std::optional<std::vector<uint8_t>> bo);
+        static std::expected<MySecondObject, genORM::error> from_row(genORM::database& __db, std::vector<genORM::value_variant>& row);
 
     public:
-        static std::expected<MySecondObject, std::string> create(genORM::database& __db, int64_t l, std::optional<int64_t> lo);
-        static std::expected<std::optional<MySecondObject>, std::string> find_by_rowid(genORM::database& __db, uint64_t);
-        static std::expected<std::optional<MySecondObject>, std::string> find_first_by_lo(genORM::database& __db, std::optional<int64_t> lo);
-        static std::expected<std::vector<MySecondObject>, std::string> find_all_by_lo(genORM::database& __db, std::optional<int64_t> lo);
+        /// Creates the table and indexes of the type, unless they already exist.
+        static std::expected<void, genORM::error> ensure_schema(genORM::database& __db);
+        static std::expected<MySecondObject, genORM::error> create(genORM::database& __db, int64_t l, std::optional<int64_t> lo, std::string s, std::optional<std::string> so, float f, std::optional<double> d, bool b, std::optional<std::vector<uint8_t>> bo);
+        static std::expected<std::optional<MySecondObject>, genORM::error> find_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<std::optional<MySecondObject>, genORM::error> find_first_by_lo(genORM::database& __db, std::optional<int64_t> lo);
+        static std::expected<std::vector<MySecondObject>, genORM::error> find_all_by_lo(genORM::database& __db, std::optional<int64_t> lo);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MySecondObject>, genORM::error> iterate_all_by_lo(genORM::database& __db, std::optional<int64_t> lo);
+        static std::expected<std::optional<MySecondObject>, genORM::error> find_first_by_s(genORM::database& __db, std::string s);
+        static std::expected<std::vector<MySecondObject>, genORM::error> find_all_by_s(genORM::database& __db, std::string s);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MySecondObject>, genORM::error> iterate_all_by_s(genORM::database& __db, std::string s);
+        static std::expected<std::optional<MySecondObject>, genORM::error> find_first_by_b(genORM::database& __db, bool b);
+        static std::expected<std::vector<MySecondObject>, genORM::error> find_all_by_b(genORM::database& __db, bool b);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MySecondObject>, genORM::error> iterate_all_by_b(genORM::database& __db, bool b);
+        static std::expected<std::optional<MySecondObject>, genORM::error> find_by_l(genORM::database& __db, int64_t l);
+        static std::expected<uint64_t, genORM::error> delete_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<uint64_t, genORM::error> delete_all_by_l(genORM::database& __db, int64_t l);
+        static std::expected<uint64_t, genORM::error> delete_all_by_lo(genORM::database& __db, std::optional<int64_t> lo);
+        static std::expected<uint64_t, genORM::error> delete_all_by_s(genORM::database& __db, std::string s);
+        static std::expected<uint64_t, genORM::error> delete_all_by_b(genORM::database& __db, bool b);
+        /// Writes the members changed through the setters since the object was read or last saved, other columns keep
+        /// the values they have in the database.
+        std::expected<void, genORM::error> save();
+        std::expected<void, genORM::error> remove() const;
+        /// At most one object, as the reference is unique
+        std::expected<std::vector<MyFifthObject>, genORM::error> get_details() const;
 
         [[nodiscard]] int64_t get_l() const { return _l; }
         [[nodiscard]] std::optional<int64_t> get_lo() const { return _lo; }
+        [[nodiscard]] const std::string& get_s() const { return _s; }
+        [[nodiscard]] const std::optional<std::string>& get_so() const { return _so; }
+        [[nodiscard]] float get_f() const { return _f; }
+        [[nodiscard]] std::optional<double> get_d() const { return _d; }
+        [[nodiscard]] bool get_b() const { return _b; }
+        [[nodiscard]] const std::optional<std::vector<uint8_t>>& get_bo() const { return _bo; }
+        void set_l(int64_t l) { _l = l; _changed.set(0); }
+        void set_lo(std::optional<int64_t> lo) { _lo = lo; _changed.set(1); }
+        void set_s(std::string s) { _s = std::move(s); _changed.set(2); }
+        void set_so(std::optional<std::string> so) { _so = std::move(so); _changed.set(3); }
+        void set_f(float f) { _f = f; _changed.set(4); }
+        void set_d(std::optional<double> d) { _d = d; _changed.set(5); }
+        void set_b(bool b) { _b = b; _changed.set(6); }
+        void set_bo(std::optional<std::vector<uint8_t>> bo) { _bo = std::move(bo); _changed.set(7); }
+    private:
+        std::bitset<8> _changed;
+    };
+
+    class MyThirdObject final : public genORM::object {
+        Color _c;
+        std::optional<Shape> _sh;
+        std::chrono::sys_time<std::chrono::microseconds> _ts;
+        std::optional<std::chrono::sys_time<std::chrono::milliseconds>> _tt;
+
+        explicit MyThirdObject(genORM::database& __db, uint64_t __id, Color c, std::optional<Shape> sh, std::chrono::sys_time<std::chrono::microseconds> ts, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt);
+        static std::expected<MyThirdObject, genORM::error> from_row(genORM::database& __db, std::vector<genORM::value_variant>& row);
+        friend class MyObject;
+
+    public:
+        /// Creates the table and indexes of the type, unless they already exist.
+        static std::expected<void, genORM::error> ensure_schema(genORM::database& __db);
+        static std::expected<MyThirdObject, genORM::error> create(genORM::database& __db, Color c, std::optional<Shape> sh, std::chrono::sys_time<std::chrono::microseconds> ts, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt);
+        static std::expected<std::optional<MyThirdObject>, genORM::error> find_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<std::optional<MyThirdObject>, genORM::error> find_first_by_c(genORM::database& __db, Color c);
+        static std::expected<std::vector<MyThirdObject>, genORM::error> find_all_by_c(genORM::database& __db, Color c);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyThirdObject>, genORM::error> iterate_all_by_c(genORM::database& __db, Color c);
+        static std::expected<std::optional<MyThirdObject>, genORM::error> find_first_by_sh(genORM::database& __db, std::optional<Shape> sh);
+        static std::expected<std::vector<MyThirdObject>, genORM::error> find_all_by_sh(genORM::database& __db, std::optional<Shape> sh);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyThirdObject>, genORM::error> iterate_all_by_sh(genORM::database& __db, std::optional<Shape> sh);
+        static std::expected<std::optional<MyThirdObject>, genORM::error> find_first_by_ts(genORM::database& __db, std::chrono::sys_time<std::chrono::microseconds> ts);
+        static std::expected<std::vector<MyThirdObject>, genORM::error> find_all_by_ts(genORM::database& __db, std::chrono::sys_time<std::chrono::microseconds> ts);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyThirdObject>, genORM::error> iterate_all_by_ts(genORM::database& __db, std::chrono::sys_time<std::chrono::microseconds> ts);
+        static std::expected<std::optional<MyThirdObject>, genORM::error> find_first_by_tt(genORM::database& __db, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt);
+        static std::expected<std::vector<MyThirdObject>, genORM::error> find_all_by_tt(genORM::database& __db, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyThirdObject>, genORM::error> iterate_all_by_tt(genORM::database& __db, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt);
+        static std::expected<std::optional<MyThirdObject>, genORM::error> find_by_c_and_sh(genORM::database& __db, Color c, std::optional<Shape> sh);
+        static std::expected<std::vector<MyThirdObject>, genORM::error> find_all_by_ts_between(genORM::database& __db, std::chrono::sys_time<std::chrono::microseconds> __from, std::chrono::sys_time<std::chrono::microseconds> __to);
+        static std::expected<std::vector<MyThirdObject>, genORM::error> find_all_by_tt_between(genORM::database& __db, std::chrono::sys_time<std::chrono::milliseconds> __from, std::chrono::sys_time<std::chrono::milliseconds> __to);
+        static std::expected<uint64_t, genORM::error> delete_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<uint64_t, genORM::error> delete_all_by_c(genORM::database& __db, Color c);
+        static std::expected<uint64_t, genORM::error> delete_all_by_sh(genORM::database& __db, std::optional<Shape> sh);
+        static std::expected<uint64_t, genORM::error> delete_all_by_ts(genORM::database& __db, std::chrono::sys_time<std::chrono::microseconds> ts);
+        static std::expected<uint64_t, genORM::error> delete_all_by_tt(genORM::database& __db, std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt);
+        /// Writes the members changed through the setters since the object was read or last saved, other columns keep
+        /// the values they have in the database.
+        std::expected<void, genORM::error> save();
+        std::expected<void, genORM::error> remove() const;
+
+        [[nodiscard]] Color get_c() const { return _c; }
+        [[nodiscard]] std::optional<Shape> get_sh() const { return _sh; }
+        [[nodiscard]] std::chrono::sys_time<std::chrono::microseconds> get_ts() const { return _ts; }
+        [[nodiscard]] std::optional<std::chrono::sys_time<std::chrono::milliseconds>> get_tt() const { return _tt; }
+        void set_c(Color c) { _c = c; _changed.set(0); }
+        void set_sh(std::optional<Shape> sh) { _sh = sh; _changed.set(1); }
+        void set_ts(std::chrono::sys_time<std::chrono::microseconds> ts) { _ts = ts; _changed.set(2); }
+        void set_tt(std::optional<std::chrono::sys_time<std::chrono::milliseconds>> tt) { _tt = tt; _changed.set(3); }
+    private:
+        std::bitset<4> _changed;
+    };
+
+    class MyFourthObject final : public genORM::object {
+        uint64_t _o;
+        std::optional<uint64_t> _so;
+
+        explicit MyFourthObject(genORM::database& __db, uint64_t __id, uint64_t o, std::optional<uint64_t> so);
+        static std::expected<MyFourthObject, genORM::error> from_row(genORM::database& __db, std::vector<genORM::value_variant>& row);
+
+    public:
+        /// Creates the table and indexes of the type, unless they already exist.
+        static std::expected<void, genORM::error> ensure_schema(genORM::database& __db);
+        static std::expected<MyFourthObject, genORM::error> create(genORM::database& __db, uint64_t o, std::optional<uint64_t> so);
+        static std::expected<std::optional<MyFourthObject>, genORM::error> find_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<std::optional<MyFourthObject>, genORM::error> find_first_by_o(genORM::database& __db, uint64_t o);
+        static std::expected<std::vector<MyFourthObject>, genORM::error> find_all_by_o(genORM::database& __db, uint64_t o);
+        /// Decodes the matching objects one at a time while the returned range is iterated. The range must be stored in
+        /// a named variable before iterating, the iterators dangle once a temporary range is destroyed.
+        static std::expected<genORM::object_range<MyFourthObject>, genORM::error> iterate_all_by_o(genORM::database& __db, uint64_t o);
+        static std::expected<uint64_t, genORM::error> delete_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<uint64_t, genORM::error> delete_all_by_o(genORM::database& __db, uint64_t o);
+        /// Writes the members changed through the setters since the object was read or last saved, other columns keep
+        /// the values they have in the database.
+        std::expected<void, genORM::error> save();
+        std::expected<void, genORM::error> remove() const;
+
+        [[nodiscard]] uint64_t get_o() const { return _o; }
+        std::expected<std::optional<MyObject>, genORM::error> get_o_object() const;
+        [[nodiscard]] std::optional<uint64_t> get_so() const { return _so; }
+        std::expected<std::optional<MySecondObject>, genORM::error> get_so_object() const;
+        void set_o(uint64_t o) { _o = o; _changed.set(0); }
+        void set_so(std::optional<uint64_t> so) { _so = so; _changed.set(1); }
+    private:
+        std::bitset<2> _changed;
+    };
+
+    class MyFifthObject final : public genORM::object {
+        uint64_t _owner;
+        std::string _title;
+
+        explicit MyFifthObject(genORM::database& __db, uint64_t __id, uint64_t owner, std::string title);
+        static std::expected<MyFifthObject, genORM::error> from_row(genORM::database& __db, std::vector<genORM::value_variant>& row);
+
+    public:
+        /// Creates the table and indexes of the type, unless they already exist.
+        static std::expected<void, genORM::error> ensure_schema(genORM::database& __db);
+        static std::expected<MyFifthObject, genORM::error> create(genORM::database& __db, uint64_t owner, std::string title);
+        static std::expected<std::optional<MyFifthObject>, genORM::error> find_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<std::optional<MyFifthObject>, genORM::error> find_by_owner(genORM::database& __db, uint64_t owner);
+        static std::expected<uint64_t, genORM::error> delete_by_rowid(genORM::database& __db, uint64_t);
+        static std::expected<uint64_t, genORM::error> delete_all_by_owner(genORM::database& __db, uint64_t owner);
+        /// Writes the members changed through the setters since the object was read or last saved, other columns keep
+        /// the values they have in the database.
+        std::expected<void, genORM::error> save();
+        std::expected<void, genORM::error> remove() const;
+        /// Links between objects of the same type
+        std::expected<std::vector<MyFifthObject>, genORM::error> get_links() const;
+        std::expected<void, genORM::error> link_links(const MyFifthObject& other) const;
+        std::expected<void, genORM::error> unlink_links(const MyFifthObject& other) const;
+
+        [[nodiscard]] uint64_t get_owner() const { return _owner; }
+        std::expected<std::optional<MySecondObject>, genORM::error> get_owner_object() const;
+        [[nodiscard]] const std::string& get_title() const { return _title; }
+        void set_owner(uint64_t owner) { _owner = owner; _changed.set(0); }
+        void set_title(std::string title) { _title = std::move(title); _changed.set(1); }
+    private:
+        std::bitset<2> _changed;
     };
 
+    /// Creates the tables and indexes of every type in a single transaction, which is nested in the
+    /// transaction of the caller if there is one. Objects can only be created afterwards. Lookups on a database
+    /// without any tables find nothing, otherwise tables missing from the database are reported as schema_mismatch.
+    std::expected<void, genORM::error> initialize_schema(genORM::database& __db);
+    /// Version of the schema described by the migrations of the config.
+    inline constexpr int64_t schema_version = 6;
+    /// Brings an existing database up to schema_version, recording progress in PRAGMA user_version. Call it after
+    /// opening the database, before initialize_schema and outside any transaction.
+    std::expected<void, genORM::error> migrate(genORM::database& __db);
 }
@@ -1,7 +1,11 @@
 #include <genORM/genORM.h>
 #include "TestProj.orm.h"
 #include <gtest/gtest.h>
+#include <sqlite3.h>
 #include <filesystem>
+#include <future>
+
+using namespace std::string_literals;
 
 TEST(genORM, open_or_create) {
 	{
@@ -13,6 +17,7 @@ TEST(genORM, open_or_create) {
 	{
 		auto db = genORM::database::open_or_create("test.db");
 		EXPECT_TRUE(db);
+		EXPECT_TRUE(testproj::initialize_schema(*db));
 		auto obj = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
 	}
 
@@ -25,6 +30,7 @@ TEST(genORM, open_or_create) {
 TEST(genORM, execute_transaction_basic) {
 	std::filesystem::remove("test.db");
 	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
 	const auto transaction_result = db->execute_transaction<testproj::MyObject>([](genORM::database& db_) {
 		return testproj::MyObject::create(db_, 15, 16, {1, 2, 3});
 	});
@@ -36,6 +42,7 @@ TEST(genORM, execute_transaction_basic) {
 TEST(genORM, execute_transaction_complex) {
 	std::filesystem::remove("test.db");
 	auto db_1 = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db_1));
 	auto db_2 = genORM::database::open("test.db");
 	const auto transaction_result = db_1->execute_transaction<testproj::MyObject>([&](genORM::database& db_) {
 		// Upgrade transaction to a write transaction
@@ -56,6 +63,7 @@ TEST(genORM, execute_transaction_complex) {
 TEST(genORM, find_by_rowid) {
 	std::filesystem::remove("test.db");
 	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
 	const auto objA = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
 	EXPECT_TRUE(objA);
 	const auto objB = testproj::MyObject::find_by_rowid(*db, objA.value().get_rowid());
@@ -69,6 +77,7 @@ TEST(genORM, find_by_rowid) {
 TEST(genORM, find_first) {
 	std::filesystem::remove("test.db");
 	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
 	const auto objA = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
 	EXPECT_TRUE(objA);
 	const auto objB = testproj::MyObject::find_first_by_i(*db, 15);
@@ -85,6 +94,7 @@ TEST(genORM, find_first) {
 TEST(genORM, find_all) {
 	std::filesystem::remove("test.db");
 	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
 	const auto obj1 = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
 	const auto obj2 = testproj::MyObject::create(*db, 15, 16, {1, 2, 3});
 	const auto obj3 = testproj::MyObject::create(*db, 15, 200, {4, 5, 6});
@@ -106,3 +116,1085 @@ TEST(genORM, find_all) {
 	EXPECT_EQ((*all)[3].get_i(), 15);
 	EXPECT_EQ((*all)[3].get_io(), obj4->get_io());
 }
+
+TEST(genORM, save) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	auto obj = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
+	EXPECT_TRUE(obj);
+	obj->set_i(16);
+	obj->set_io(17);
+	obj->set_ba({4, 5});
+	EXPECT_TRUE(obj->save());
+
+	const auto found = testproj::MyObject::find_by_rowid(*db, obj->get_rowid());
+	EXPECT_TRUE(found);
+	EXPECT_TRUE(*found);
+	EXPECT_EQ((*found)->get_i(), 16);
+	EXPECT_EQ((*found)->get_io(), 17);
+	EXPECT_EQ((*found)->get_ba(), std::vector<uint8_t>({4, 5}));
+
+	obj->set_io(std::nullopt);
+	EXPECT_TRUE(obj->save());
+	const auto found_again = testproj::MyObject::find_by_rowid(*db, obj->get_rowid());
+	EXPECT_EQ((*found_again)->get_io(), std::nullopt);
+}
+
+TEST(genORM, save_changed_members_only) {
+	std::filesystem::remove("test.db");
+	auto db_1 = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db_1));
+	auto db_2 = genORM::database::open("test.db");
+	auto obj_1 = testproj::MyObject::create(*db_1, 15, std::nullopt, {1, 2, 3});
+	ASSERT_TRUE(obj_1);
+	auto obj_2 = testproj::MyObject::find_by_rowid(*db_2, obj_1->get_rowid());
+	ASSERT_TRUE(obj_2 && *obj_2);
+
+	obj_1->set_i(16);
+	EXPECT_TRUE(obj_1->save());
+	(*obj_2)->set_io(17);
+	EXPECT_TRUE((*obj_2)->save());
+	// Saving without changes leaves the row alone
+	EXPECT_TRUE(obj_1->save());
+
+	const auto found = testproj::MyObject::find_by_rowid(*db_1, obj_1->get_rowid());
+	ASSERT_TRUE(found && *found);
+	EXPECT_EQ((*found)->get_i(), 16);
+	EXPECT_EQ((*found)->get_io(), 17);
+	EXPECT_EQ((*found)->get_ba(), std::vector<uint8_t>({1, 2, 3}));
+}
+
+TEST(genORM, remove) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
+	EXPECT_TRUE(obj);
+	EXPECT_TRUE(obj->remove());
+	const auto found = testproj::MyObject::find_by_rowid(*db, obj->get_rowid());
+	EXPECT_TRUE(found);
+	EXPECT_FALSE(*found);
+	EXPECT_FALSE(obj->remove()); // Already removed
+}
+
+TEST(genORM, delete_by) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
+	const auto obj2 = testproj::MyObject::create(*db, 15, 16, {1, 2, 3});
+	const auto obj3 = testproj::MyObject::create(*db, 17, 16, {4, 5, 6});
+
+	const auto deleted_by_rowid = testproj::MyObject::delete_by_rowid(*db, obj3->get_rowid());
+	EXPECT_TRUE(deleted_by_rowid);
+	EXPECT_EQ(*deleted_by_rowid, 1);
+
+	const auto deleted_none = testproj::MyObject::delete_all_by_i(*db, 17);
+	EXPECT_TRUE(deleted_none);
+	EXPECT_EQ(*deleted_none, 0);
+
+	const auto deleted_all = testproj::MyObject::delete_all_by_i(*db, 15);
+	EXPECT_TRUE(deleted_all);
+	EXPECT_EQ(*deleted_all, 2);
+
+	const auto remaining = testproj::MyObject::find_all_by_i(*db, 15);
+	EXPECT_TRUE(remaining);
+	EXPECT_TRUE(remaining->empty());
+}
+
+TEST(genORM, text) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MySecondObject::create(*db, 1, std::nullopt, "hello", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	const auto obj2 = testproj::MySecondObject::create(*db, 2, std::nullopt, "hello", "", 0.0f, std::nullopt, false, std::nullopt);
+	const auto obj3 = testproj::MySecondObject::create(*db, 3, std::nullopt, "world", "with\0null"s, 0.0f, std::nullopt, false, std::nullopt);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+	EXPECT_TRUE(obj3);
+
+	const auto first = testproj::MySecondObject::find_first_by_s(*db, "world");
+	EXPECT_TRUE(first);
+	EXPECT_TRUE(*first);
+	EXPECT_EQ((*first)->get_l(), 3);
+	EXPECT_EQ((*first)->get_so(), "with\0null"s);
+
+	const auto all = testproj::MySecondObject::find_all_by_s(*db, "hello");
+	EXPECT_TRUE(all);
+	EXPECT_EQ(all->size(), 2);
+	EXPECT_EQ((*all)[0].get_so(), std::nullopt);
+	EXPECT_EQ((*all)[1].get_so(), "");
+}
+
+TEST(genORM, floating_point) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MySecondObject::create(*db, 1, std::nullopt, "", std::nullopt, 1.5f, std::nullopt, false, std::nullopt);
+	const auto obj2 = testproj::MySecondObject::create(*db, 2, std::nullopt, "", std::nullopt, -0.25f, 3.141592653589793, false, std::nullopt);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+
+	const auto found1 = testproj::MySecondObject::find_by_rowid(*db, obj1->get_rowid());
+	EXPECT_EQ((*found1)->get_f(), 1.5f);
+	EXPECT_EQ((*found1)->get_d(), std::nullopt);
+	const auto found2 = testproj::MySecondObject::find_by_rowid(*db, obj2->get_rowid());
+	EXPECT_EQ((*found2)->get_f(), -0.25f);
+	EXPECT_EQ((*found2)->get_d(), 3.141592653589793);
+}
+
+TEST(genORM, boolean) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MySecondObject::create(*db, 1, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, true, std::nullopt);
+	const auto obj2 = testproj::MySecondObject::create(*db, 2, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	const auto obj3 = testproj::MySecondObject::create(*db, 3, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, true, std::nullopt);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+	EXPECT_TRUE(obj3);
+
+	const auto flagged = testproj::MySecondObject::find_all_by_b(*db, true);
+	EXPECT_TRUE(flagged);
+	EXPECT_EQ(flagged->size(), 2);
+	EXPECT_TRUE((*flagged)[0].get_b());
+	EXPECT_EQ((*flagged)[0].get_l(), 1);
+	EXPECT_EQ((*flagged)[1].get_l(), 3);
+
+	const auto unflagged = testproj::MySecondObject::find_all_by_b(*db, false);
+	EXPECT_TRUE(unflagged);
+	EXPECT_EQ(unflagged->size(), 1);
+	EXPECT_FALSE((*unflagged)[0].get_b());
+}
+
+TEST(genORM, enums) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyThirdObject::create(*db, testproj::Color::GREEN, std::nullopt, {}, std::nullopt);
+	const auto obj2 = testproj::MyThirdObject::create(*db, testproj::Color::BLUE, testproj::Shape::SQUARE, {}, std::nullopt);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+
+	const auto found1 = testproj::MyThirdObject::find_first_by_c(*db, testproj::Color::GREEN);
+	EXPECT_TRUE(found1);
+	EXPECT_TRUE(*found1);
+	EXPECT_EQ((*found1)->get_c(), testproj::Color::GREEN);
+	EXPECT_EQ((*found1)->get_sh(), std::nullopt);
+
+	const auto found2 = testproj::MyThirdObject::find_all_by_sh(*db, testproj::Shape::SQUARE);
+	EXPECT_TRUE(found2);
+	EXPECT_EQ(found2->size(), 1);
+	EXPECT_EQ((*found2)[0].get_c(), testproj::Color::BLUE);
+	EXPECT_EQ((*found2)[0].get_sh(), testproj::Shape::SQUARE);
+}
+
+TEST(genORM, enums_unknown_value) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyThirdObject::create(*db, testproj::Color::RED, testproj::Shape::CIRCLE, {}, std::nullopt);
+	const auto obj2 = testproj::MyThirdObject::create(*db, testproj::Color::RED, testproj::Shape::SQUARE, {}, std::nullopt);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+
+	// Corrupt the stored values behind genORM's back
+	sqlite3* raw_db{};
+	ASSERT_EQ(sqlite3_open("test.db", &raw_db), SQLITE_OK);
+	EXPECT_EQ(sqlite3_exec(raw_db, "UPDATE MyThirdObject SET c = 7 WHERE __id = 1;", nullptr, nullptr, nullptr), SQLITE_OK);
+	EXPECT_EQ(sqlite3_exec(raw_db, "UPDATE MyThirdObject SET sh = 'TRIANGLE' WHERE __id = 2;", nullptr, nullptr, nullptr), SQLITE_OK);
+	sqlite3_close(raw_db);
+
+	EXPECT_FALSE(testproj::MyThirdObject::find_by_rowid(*db, obj1->get_rowid()));
+	EXPECT_FALSE(testproj::MyThirdObject::find_by_rowid(*db, obj2->get_rowid()));
+	EXPECT_FALSE(testproj::MyThirdObject::find_all_by_c(*db, testproj::Color::RED));
+}
+
+TEST(genORM, timestamps) {
+	using namespace std::chrono;
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto t1 = sys_days{2024y / 2 / 29} + 13h + 14min + 15s + 123456us;
+	const auto t2 = sys_days{1969y / 12 / 31} + 23h + 59min + 59s + 999ms;
+	const auto t3 = sys_days{2031y / 1 / 1};
+	const auto obj1 = testproj::MyThirdObject::create(*db, testproj::Color::RED, std::nullopt, t1, time_point_cast<milliseconds>(t1));
+	const auto obj2 = testproj::MyThirdObject::create(*db, testproj::Color::RED, std::nullopt, t2, time_point_cast<milliseconds>(t2));
+	const auto obj3 = testproj::MyThirdObject::create(*db, testproj::Color::RED, std::nullopt, t3, std::nullopt);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+	EXPECT_TRUE(obj3);
+
+	const auto found1 = testproj::MyThirdObject::find_by_rowid(*db, obj1->get_rowid());
+	EXPECT_EQ((*found1)->get_ts(), t1);
+	EXPECT_EQ((*found1)->get_tt(), time_point_cast<milliseconds>(t1));
+	const auto found2 = testproj::MyThirdObject::find_first_by_tt(*db, time_point_cast<milliseconds>(t2));
+	EXPECT_TRUE(*found2);
+	EXPECT_EQ((*found2)->get_ts(), t2);
+	const auto found3 = testproj::MyThirdObject::find_by_rowid(*db, obj3->get_rowid());
+	EXPECT_EQ((*found3)->get_tt(), std::nullopt);
+
+	const auto in_range = testproj::MyThirdObject::find_all_by_ts_between(*db, t2, t1);
+	EXPECT_TRUE(in_range);
+	EXPECT_EQ(in_range->size(), 2);
+	const auto text_in_range = testproj::MyThirdObject::find_all_by_tt_between(*db, sys_days{2000y / 1 / 1}, sys_days{2100y / 1 / 1});
+	EXPECT_TRUE(text_in_range);
+	EXPECT_EQ(text_in_range->size(), 1);
+	EXPECT_EQ((*text_in_range)[0].get_rowid(), obj1->get_rowid());
+}
+
+TEST(genORM, timestamp_text) {
+	using namespace std::chrono;
+	const auto time = sys_days{2024y / 2 / 29} + 13h + 14min + 15s + 123456789ns;
+	EXPECT_EQ(genORM::format_timestamp(time, 0), "2024-02-29T13:14:15Z");
+	EXPECT_EQ(genORM::format_timestamp(time, 3), "2024-02-29T13:14:15.123Z");
+	EXPECT_EQ(genORM::format_timestamp(time, 9), "2024-02-29T13:14:15.123456789Z");
+	EXPECT_EQ(genORM::parse_timestamp("2024-02-29T13:14:15.123456789Z"), time);
+	EXPECT_EQ(genORM::parse_timestamp("2024-02-29T13:14:15.1Z"), sys_days{2024y / 2 / 29} + 13h + 14min + 15s + 100ms);
+	EXPECT_FALSE(genORM::parse_timestamp("2023-02-29T13:14:15Z"));
+	EXPECT_FALSE(genORM::parse_timestamp("2024-02-29 13:14:15Z"));
+	EXPECT_FALSE(genORM::parse_timestamp("2024-02-29T13:14:15.Z"));
+}
+
+TEST(genORM, timestamp_text_range) {
+	using namespace std::chrono;
+	// Formatting and parsing work at the precision of the time point, far from 1970 as well
+	const auto far_future = sys_days{9999y / 12 / 31} + 23h + 59min + 59s + 999ms;
+	const auto far_past = sys_days{1500y / 6 / 15} + 1h + 2min + 3s + 4ms;
+	const auto beyond_future = sys_days{year{12000} / 2 / 29} + 12h;
+	const auto before_year_zero = sys_days{year{-50} / 3 / 1} + 500ms;
+	EXPECT_EQ(genORM::format_timestamp(far_future, 3), "9999-12-31T23:59:59.999Z");
+	EXPECT_EQ(genORM::format_timestamp(far_past, 3), "1500-06-15T01:02:03.004Z");
+	EXPECT_EQ(genORM::format_timestamp(beyond_future, 0), "+12000-02-29T12:00:00Z");
+	EXPECT_EQ(genORM::format_timestamp(before_year_zero, 3), "-0050-03-01T00:00:00.500Z");
+	const std::vector<sys_time<milliseconds>> times{far_future, far_past, beyond_future, before_year_zero};
+	for (const auto time : times) {
+		EXPECT_EQ(genORM::parse_timestamp<milliseconds>(genORM::format_timestamp(time, 3)), time);
+	}
+	EXPECT_EQ(genORM::parse_timestamp<seconds>(genORM::format_timestamp(time_point_cast<seconds>(sys_days::max()), 0)), time_point_cast<seconds>(sys_days::max()));
+
+	// Nanoseconds only reach from 1677 to 2262
+	const auto out_of_range = genORM::parse_timestamp("9999-12-31T23:59:59Z");
+	ASSERT_FALSE(out_of_range);
+	EXPECT_EQ(out_of_range.error().category, genORM::error_category::invalid_value);
+	EXPECT_FALSE(genORM::parse_timestamp<milliseconds>("10000-01-01T00:00:00Z"));
+	EXPECT_FALSE(genORM::parse_timestamp<milliseconds>("+999-01-01T00:00:00Z"));
+
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	for (const auto time : times) {
+		const auto obj = testproj::MyThirdObject::create(*db, testproj::Color::RED, std::nullopt, time, time);
+		ASSERT_TRUE(obj);
+		const auto found = testproj::MyThirdObject::find_by_rowid(*db, obj->get_rowid());
+		ASSERT_TRUE(found);
+		ASSERT_TRUE(*found);
+		EXPECT_EQ((*found)->get_ts(), time);
+		EXPECT_EQ((*found)->get_tt(), time);
+	}
+}
+
+TEST(genORM, bytearray) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyObject::create(*db, 1, std::nullopt, {});
+	const auto obj2 = testproj::MyObject::create(*db, 2, std::nullopt, {0xde, 0xad});
+	const auto obj3 = testproj::MyObject::create(*db, 3, std::nullopt, {0xde, 0xad});
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+	EXPECT_TRUE(obj3);
+
+	const auto empty = testproj::MyObject::find_first_by_ba(*db, {});
+	EXPECT_TRUE(empty);
+	EXPECT_TRUE(*empty);
+	EXPECT_EQ((*empty)->get_i(), 1);
+	const auto found = testproj::MyObject::find_all_by_ba(*db, {0xde, 0xad});
+	EXPECT_TRUE(found);
+	EXPECT_EQ(found->size(), 2);
+	EXPECT_EQ((*found)[0].get_i(), 2);
+	EXPECT_EQ((*found)[1].get_i(), 3);
+}
+
+TEST(genORM, nullable_bytearray) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MySecondObject::create(*db, 1, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	const auto obj2 = testproj::MySecondObject::create(*db, 2, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, false, std::vector<uint8_t>{});
+	const auto obj3 = testproj::MySecondObject::create(*db, 3, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, false, std::vector<uint8_t>{1, 2});
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+	EXPECT_TRUE(obj3);
+
+	const auto found1 = testproj::MySecondObject::find_by_rowid(*db, obj1->get_rowid());
+	EXPECT_EQ((*found1)->get_bo(), std::nullopt);
+	const auto found2 = testproj::MySecondObject::find_by_rowid(*db, obj2->get_rowid());
+	EXPECT_EQ((*found2)->get_bo(), std::vector<uint8_t>{});
+	const auto found3 = testproj::MySecondObject::find_by_rowid(*db, obj3->get_rowid());
+	EXPECT_EQ((*found3)->get_bo(), std::vector<uint8_t>({1, 2}));
+}
+
+TEST(genORM, find_null) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyObject::create(*db, 15, std::nullopt, {1, 2, 3});
+	const auto obj2 = testproj::MyObject::create(*db, 16, 16, {1, 2, 3});
+	const auto obj3 = testproj::MyObject::create(*db, 17, std::nullopt, {4, 5, 6});
+
+	const auto first = testproj::MyObject::find_first_by_io(*db, std::nullopt);
+	EXPECT_TRUE(first);
+	EXPECT_TRUE(*first);
+	EXPECT_EQ((*first)->get_i(), 15);
+
+	const auto all = testproj::MyObject::find_all_by_io(*db, std::nullopt);
+	EXPECT_TRUE(all);
+	EXPECT_EQ(all->size(), 2);
+	EXPECT_EQ((*all)[0].get_i(), 15);
+	EXPECT_EQ((*all)[1].get_i(), 17);
+
+	const auto non_null = testproj::MyObject::find_all_by_io(*db, 16);
+	EXPECT_TRUE(non_null);
+	EXPECT_EQ(non_null->size(), 1);
+	EXPECT_EQ((*non_null)[0].get_i(), 16);
+
+	const auto deleted = testproj::MyObject::delete_all_by_io(*db, std::nullopt);
+	EXPECT_TRUE(deleted);
+	EXPECT_EQ(*deleted, 2);
+}
+
+TEST(genORM, unique) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MySecondObject::create(*db, 1, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	EXPECT_TRUE(obj1);
+	const auto obj2 = testproj::MySecondObject::create(*db, 1, std::nullopt, "", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	EXPECT_FALSE(obj2);
+	EXPECT_TRUE(genORM::is_unique_violation(obj2.error()));
+
+	const auto found = testproj::MySecondObject::find_by_l(*db, 1);
+	EXPECT_TRUE(found);
+	EXPECT_TRUE(*found);
+	EXPECT_EQ((*found)->get_rowid(), obj1->get_rowid());
+	const auto not_found = testproj::MySecondObject::find_by_l(*db, 2);
+	EXPECT_TRUE(not_found);
+	EXPECT_FALSE(*not_found);
+}
+
+TEST(genORM, unique_constraint) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyThirdObject::create(*db, testproj::Color::RED, testproj::Shape::CIRCLE, {}, std::nullopt);
+	const auto obj2 = testproj::MyThirdObject::create(*db, testproj::Color::RED, testproj::Shape::SQUARE, {}, std::nullopt);
+	const auto obj3 = testproj::MyThirdObject::create(*db, testproj::Color::GREEN, testproj::Shape::CIRCLE, {}, std::nullopt);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(obj2);
+	EXPECT_TRUE(obj3);
+	const auto duplicate = testproj::MyThirdObject::create(*db, testproj::Color::RED, testproj::Shape::SQUARE, {}, std::nullopt);
+	EXPECT_FALSE(duplicate);
+	EXPECT_TRUE(genORM::is_unique_violation(duplicate.error()));
+
+	const auto found = testproj::MyThirdObject::find_by_c_and_sh(*db, testproj::Color::RED, testproj::Shape::SQUARE);
+	EXPECT_TRUE(found);
+	EXPECT_TRUE(*found);
+	EXPECT_EQ((*found)->get_rowid(), obj2->get_rowid());
+	const auto not_found = testproj::MyThirdObject::find_by_c_and_sh(*db, testproj::Color::BLUE, testproj::Shape::SQUARE);
+	EXPECT_TRUE(not_found);
+	EXPECT_FALSE(*not_found);
+}
+
+TEST(genORM, composite_index) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	const auto obj2 = testproj::MyObject::create(*db, 15, 16, {2});
+	const auto obj3 = testproj::MyObject::create(*db, 15, 16, {3});
+	const auto obj4 = testproj::MyObject::create(*db, 17, 16, {4});
+
+	const auto first = testproj::MyObject::find_first_by_i_and_io(*db, 15, 16);
+	EXPECT_TRUE(first);
+	EXPECT_TRUE(*first);
+	EXPECT_EQ((*first)->get_ba(), std::vector<uint8_t>{2});
+
+	const auto all = testproj::MyObject::find_all_by_i_and_io(*db, 15, 16);
+	EXPECT_TRUE(all);
+	EXPECT_EQ(all->size(), 2);
+	EXPECT_EQ((*all)[0].get_ba(), std::vector<uint8_t>{2});
+	EXPECT_EQ((*all)[1].get_ba(), std::vector<uint8_t>{3});
+
+	const auto null_key = testproj::MyObject::find_all_by_i_and_io(*db, 15, std::nullopt);
+	EXPECT_TRUE(null_key);
+	EXPECT_EQ(null_key->size(), 1);
+	EXPECT_EQ((*null_key)[0].get_ba(), std::vector<uint8_t>{1});
+
+	const auto none = testproj::MyObject::find_first_by_i_and_io(*db, 17, 15);
+	EXPECT_TRUE(none);
+	EXPECT_FALSE(*none);
+}
+
+TEST(genORM, reference) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto parent = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	const auto second = testproj::MySecondObject::create(*db, 1, std::nullopt, "s", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	const auto child = testproj::MyFourthObject::create(*db, parent->get_rowid(), second->get_rowid());
+	EXPECT_TRUE(child);
+	EXPECT_EQ(child->get_o(), parent->get_rowid());
+
+	const auto referenced = child->get_o_object();
+	EXPECT_TRUE(referenced);
+	EXPECT_TRUE(*referenced);
+	EXPECT_EQ((*referenced)->get_i(), 15);
+	const auto children = testproj::MyFourthObject::find_all_by_o(*db, parent->get_rowid());
+	EXPECT_TRUE(children);
+	EXPECT_EQ(children->size(), 1);
+
+	const auto dangling = testproj::MyFourthObject::create(*db, parent->get_rowid() + 100, std::nullopt);
+	EXPECT_FALSE(dangling);
+	EXPECT_TRUE(genORM::is_foreign_key_violation(dangling.error()));
+}
+
+TEST(genORM, reference_on_delete) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto parent = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	const auto second = testproj::MySecondObject::create(*db, 1, std::nullopt, "s", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	const auto child = testproj::MyFourthObject::create(*db, parent->get_rowid(), second->get_rowid());
+
+	EXPECT_TRUE(second->remove());
+	const auto nulled = testproj::MyFourthObject::find_by_rowid(*db, child->get_rowid());
+	EXPECT_TRUE(nulled);
+	EXPECT_TRUE(*nulled);
+	EXPECT_FALSE((*nulled)->get_so());
+	const auto no_object = (*nulled)->get_so_object();
+	EXPECT_TRUE(no_object);
+	EXPECT_FALSE(*no_object);
+
+	EXPECT_TRUE(parent->remove());
+	const auto cascaded = testproj::MyFourthObject::find_by_rowid(*db, child->get_rowid());
+	EXPECT_TRUE(cascaded);
+	EXPECT_FALSE(*cascaded);
+}
+
+TEST(genORM, has_many) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto parent = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	const auto other = testproj::MyObject::create(*db, 16, std::nullopt, {2});
+	const auto child1 = testproj::MyFourthObject::create(*db, parent->get_rowid(), std::nullopt);
+	const auto child2 = testproj::MyFourthObject::create(*db, parent->get_rowid(), std::nullopt);
+	const auto child3 = testproj::MyFourthObject::create(*db, other->get_rowid(), std::nullopt);
+
+	const auto children = parent->get_children();
+	EXPECT_TRUE(children);
+	EXPECT_EQ(children->size(), 2);
+	EXPECT_EQ((*children)[0].get_rowid(), child1->get_rowid());
+	EXPECT_EQ((*children)[1].get_rowid(), child2->get_rowid());
+	const auto other_children = other->get_children();
+	EXPECT_TRUE(other_children);
+	EXPECT_EQ(other_children->size(), 1);
+}
+
+TEST(genORM, has_many_unique) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto parent = testproj::MySecondObject::create(*db, 1, std::nullopt, "a", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	ASSERT_TRUE(parent);
+	EXPECT_TRUE(parent->get_details());
+	EXPECT_TRUE(parent->get_details()->empty());
+
+	const auto detail = testproj::MyFifthObject::create(*db, parent->get_rowid(), "detail");
+	ASSERT_TRUE(detail);
+	const auto details = parent->get_details();
+	EXPECT_TRUE(details);
+	EXPECT_EQ(details->size(), 1);
+	EXPECT_EQ((*details)[0].get_rowid(), detail->get_rowid());
+}
+
+TEST(genORM, many_to_many) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto obj1 = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	const auto obj2 = testproj::MyObject::create(*db, 16, std::nullopt, {2});
+	const auto tag1 = testproj::MyThirdObject::create(*db, testproj::Color::RED, std::nullopt, {}, std::nullopt);
+	const auto tag2 = testproj::MyThirdObject::create(*db, testproj::Color::GREEN, std::nullopt, {}, std::nullopt);
+
+	EXPECT_TRUE(obj1->link_tags(*tag1));
+	EXPECT_TRUE(obj1->link_tags(*tag2));
+	EXPECT_TRUE(obj1->link_tags(*tag2));
+	EXPECT_TRUE(obj2->link_tags(*tag2));
+	const auto tags = obj1->get_tags();
+	EXPECT_TRUE(tags);
+	EXPECT_EQ(tags->size(), 2);
+	EXPECT_EQ((*tags)[0].get_c(), testproj::Color::RED);
+	EXPECT_EQ((*tags)[1].get_c(), testproj::Color::GREEN);
+
+	EXPECT_TRUE(obj1->unlink_tags(*tag1));
+	const auto unlinked = obj1->get_tags();
+	EXPECT_TRUE(unlinked);
+	EXPECT_EQ(unlinked->size(), 1);
+
+	EXPECT_TRUE(tag2->remove());
+	const auto removed = obj2->get_tags();
+	EXPECT_TRUE(removed);
+	EXPECT_TRUE(removed->empty());
+}
+
+TEST(genORM, many_to_many_self) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto parent1 = testproj::MySecondObject::create(*db, 1, std::nullopt, "a", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	const auto parent2 = testproj::MySecondObject::create(*db, 2, std::nullopt, "b", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	const auto obj1 = testproj::MyFifthObject::create(*db, parent1->get_rowid(), "first");
+	const auto obj2 = testproj::MyFifthObject::create(*db, parent2->get_rowid(), "second");
+
+	EXPECT_TRUE(obj1->link_links(*obj2));
+	const auto links = obj1->get_links();
+	EXPECT_TRUE(links);
+	EXPECT_EQ(links->size(), 1);
+	EXPECT_EQ((*links)[0].get_rowid(), obj2->get_rowid());
+	const auto reverse_links = obj2->get_links();
+	EXPECT_TRUE(reverse_links);
+	EXPECT_TRUE(reverse_links->empty());
+}
+
+TEST(genORM, migrate_new_database) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	EXPECT_TRUE(testproj::migrate(*db));
+	EXPECT_EQ(db->get_user_version(), testproj::schema_version);
+	EXPECT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_TRUE(testproj::MyObject::create(*db, 15, std::nullopt, {1}));
+	EXPECT_TRUE(testproj::migrate(*db));
+}
+
+TEST(genORM, migrate) {
+	std::filesystem::remove("test.db");
+	{
+		// Database created by an older version of the config
+		sqlite3* raw_db{};
+		ASSERT_EQ(sqlite3_open("test.db", &raw_db), SQLITE_OK);
+		EXPECT_EQ(sqlite3_exec(raw_db, "CREATE TABLE MyObject (__id INTEGER PRIMARY KEY NOT NULL, i INTEGER NOT NULL DEFAULT 0, ba BLOB) STRICT;"
+			"INSERT INTO MyObject (i) VALUES (15);"
+			"CREATE TABLE MySecondObject (__id INTEGER PRIMARY KEY NOT NULL, l INTEGER NOT NULL DEFAULT 0, lo INTEGER, str TEXT NOT NULL DEFAULT '', so TEXT, "
+			"f REAL NOT NULL DEFAULT 0.0, d REAL, b INTEGER NOT NULL DEFAULT 0, bo BLOB) STRICT;"
+			"INSERT INTO MySecondObject (l, str) VALUES (1, 'text');"
+			"CREATE TABLE MyThirdObject (__id INTEGER PRIMARY KEY NOT NULL, c INTEGER NOT NULL DEFAULT 0, legacy TEXT, sh TEXT, ts INTEGER NOT NULL DEFAULT 0) STRICT;"
+			"INSERT INTO MyThirdObject (c, legacy, sh, ts) VALUES (2, 'legacy', 'SQUARE', 1000000);",
+			nullptr, nullptr, nullptr), SQLITE_OK);
+		sqlite3_close(raw_db);
+	}
+
+	auto db = genORM::database::open("test.db");
+	EXPECT_EQ(db->get_user_version(), 0);
+	EXPECT_TRUE(testproj::migrate(*db));
+	EXPECT_EQ(db->get_user_version(), testproj::schema_version);
+	EXPECT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_TRUE(db->has_column("MyObject", "io"));
+	EXPECT_FALSE(*db->has_column("MyThirdObject", "legacy"));
+
+	const auto obj1 = testproj::MyObject::find_by_rowid(*db, 1);
+	EXPECT_TRUE(obj1);
+	EXPECT_TRUE(*obj1);
+	EXPECT_EQ((*obj1)->get_io(), 15);
+	EXPECT_TRUE((*obj1)->get_ba().empty());
+	// The byte array column no longer accepts null
+	EXPECT_FALSE(db->execute("UPDATE MyObject SET ba = NULL;"));
+	const auto obj2 = testproj::MySecondObject::find_by_l(*db, 1);
+	EXPECT_TRUE(obj2);
+	EXPECT_TRUE(*obj2);
+	EXPECT_EQ((*obj2)->get_s(), "text");
+	const auto obj3 = testproj::MyThirdObject::find_by_rowid(*db, 1);
+	EXPECT_TRUE(obj3);
+	EXPECT_TRUE(*obj3);
+	EXPECT_EQ((*obj3)->get_c(), testproj::Color::BLUE);
+	EXPECT_EQ((*obj3)->get_sh(), testproj::Shape::SQUARE);
+	EXPECT_EQ((*obj3)->get_ts().time_since_epoch().count(), 1000000);
+	EXPECT_FALSE((*obj3)->get_tt());
+
+	// Migrating an up-to-date database does nothing
+	EXPECT_TRUE(testproj::migrate(*db));
+	EXPECT_TRUE(testproj::MyThirdObject::create(*db, testproj::Color::RED, std::nullopt, {}, std::nullopt));
+}
+
+TEST(genORM, migrate_renamed_member) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	const auto parent = testproj::MySecondObject::create(*db, 1, std::nullopt, "a", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	ASSERT_TRUE(parent);
+	// Table as it was at version 3, before the member was added and renamed
+	EXPECT_TRUE(db->execute("DROP TABLE MyFifthObject;"));
+	EXPECT_TRUE(db->execute("CREATE TABLE MyFifthObject (__id INTEGER PRIMARY KEY NOT NULL, owner INTEGER NOT NULL REFERENCES MySecondObject(__id) ON DELETE CASCADE) STRICT;"));
+	EXPECT_TRUE(db->execute("INSERT INTO MyFifthObject (owner) VALUES (" + std::to_string(parent->get_rowid()) + ");"));
+	EXPECT_TRUE(db->set_user_version(3));
+
+	EXPECT_TRUE(testproj::migrate(*db));
+	EXPECT_TRUE(*db->get_foreign_keys());
+	EXPECT_FALSE(*db->has_column("MyFifthObject", "label"));
+	const auto obj = testproj::MyFifthObject::find_by_owner(*db, parent->get_rowid());
+	EXPECT_TRUE(obj);
+	EXPECT_TRUE(*obj);
+	EXPECT_EQ((*obj)->get_title(), "untitled");
+}
+
+TEST(genORM, migrate_foreign_keys) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db", {.foreign_keys = false});
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_TRUE(db->execute("DROP TABLE MyFifthObject;"));
+	EXPECT_TRUE(db->execute("CREATE TABLE MyFifthObject (__id INTEGER PRIMARY KEY NOT NULL, owner INTEGER NOT NULL REFERENCES MySecondObject(__id) ON DELETE CASCADE) STRICT;"));
+	EXPECT_TRUE(db->execute("INSERT INTO MyFifthObject (owner) VALUES (999);"));
+	EXPECT_TRUE(db->set_user_version(3));
+
+	// The dangling reference is reported once the table is rebuilt, and the migration is rolled back
+	const auto failed = testproj::migrate(*db);
+	EXPECT_FALSE(failed);
+	EXPECT_EQ(failed.error().category, genORM::error_category::foreign_key_violation);
+	EXPECT_EQ(db->get_user_version(), 3);
+	EXPECT_FALSE(*db->has_column("MyFifthObject", "label"));
+	// The setting of the connection survives the migration
+	EXPECT_FALSE(*db->get_foreign_keys());
+
+	EXPECT_TRUE(db->execute("DELETE FROM MyFifthObject;"));
+	EXPECT_TRUE(testproj::migrate(*db));
+	EXPECT_EQ(db->get_user_version(), testproj::schema_version);
+	EXPECT_FALSE(*db->get_foreign_keys());
+}
+
+TEST(genORM, initialize_schema) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+
+	// Lookups on a fresh database find nothing instead of failing
+	const auto by_rowid = testproj::MyObject::find_by_rowid(*db, 1);
+	EXPECT_TRUE(by_rowid);
+	EXPECT_FALSE(*by_rowid);
+	const auto all = testproj::MyObject::find_all_by_i(*db, 15);
+	EXPECT_TRUE(all);
+	EXPECT_TRUE(all->empty());
+	EXPECT_FALSE(testproj::MyObject::create(*db, 15, std::nullopt, {1}));
+
+	EXPECT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_TRUE(db->has_table("MyObject_tags"));
+	EXPECT_TRUE(testproj::initialize_schema(*db));
+	const auto obj = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	EXPECT_TRUE(obj);
+	const auto tags = obj->get_tags();
+	EXPECT_TRUE(tags);
+	EXPECT_TRUE(tags->empty());
+
+	// Once the schema is initialized, a missing table is reported instead of found empty
+	EXPECT_TRUE(db->execute("DROP TABLE MyThirdObject;"));
+	const auto missing = testproj::MyThirdObject::find_by_rowid(*db, 1);
+	ASSERT_FALSE(missing);
+	EXPECT_EQ(missing.error().category, genORM::error_category::schema_mismatch);
+	const auto missing_all = testproj::MyThirdObject::find_all_by_c(*db, testproj::Color::RED);
+	ASSERT_FALSE(missing_all);
+	EXPECT_EQ(missing_all.error().category, genORM::error_category::schema_mismatch);
+}
+
+TEST(genORM, missing_table_after_reopen) {
+	std::filesystem::remove("test.db");
+	{
+		auto db = genORM::database::open_or_create("test.db");
+		ASSERT_TRUE(testproj::initialize_schema(*db));
+		EXPECT_TRUE(db->execute("DROP TABLE MyThirdObject;"));
+	}
+
+	// Connections that did not initialize the schema themselves report the missing table as well
+	auto db = genORM::database::open("test.db");
+	ASSERT_TRUE(db);
+	const auto missing = testproj::MyThirdObject::find_all_by_c(*db, testproj::Color::RED);
+	ASSERT_FALSE(missing);
+	EXPECT_EQ(missing.error().category, genORM::error_category::schema_mismatch);
+	const auto missing_one = testproj::MyThirdObject::find_by_rowid(*db, 1);
+	ASSERT_FALSE(missing_one);
+	EXPECT_EQ(missing_one.error().category, genORM::error_category::schema_mismatch);
+	auto missing_range = testproj::MyThirdObject::iterate_all_by_c(*db, testproj::Color::RED);
+	ASSERT_FALSE(missing_range);
+	EXPECT_EQ(missing_range.error().category, genORM::error_category::schema_mismatch);
+}
+
+TEST(genORM, prepared_statement_cache) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_EQ(db->get_prepared_statement_count(), 0);
+
+	// Repeated calls reuse the statement prepared by the first one
+	const auto obj = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	EXPECT_TRUE(obj);
+	EXPECT_TRUE(testproj::MyObject::create(*db, 16, std::nullopt, {2}));
+	EXPECT_EQ(db->get_prepared_statement_count(), 1);
+	for (int i = 0; i < 3; ++i) {
+		const auto found = testproj::MyObject::find_by_rowid(*db, obj->get_rowid());
+		EXPECT_TRUE(found && *found);
+		EXPECT_EQ((*found)->get_i(), 15);
+	}
+	EXPECT_EQ(db->get_prepared_statement_count(), 2);
+
+	// Reset statements do not hold locks, so the schema can still change
+	EXPECT_TRUE(db->execute("DROP TABLE MyObject_tags;"));
+
+	// The cache moves along with the connection
+	auto moved_db = std::move(*db);
+	EXPECT_EQ(moved_db.get_prepared_statement_count(), 2);
+	const auto all = testproj::MyObject::find_all_by_i(moved_db, 16);
+	EXPECT_TRUE(all);
+	EXPECT_EQ(all->size(), 1);
+	EXPECT_EQ(moved_db.get_prepared_statement_count(), 3);
+}
+
+TEST(genORM, execute_transaction_rollback) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_FALSE(db->is_in_transaction());
+
+	// An error result rolls back
+	const auto error_result = db->execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_TRUE(db.is_in_transaction());
+		EXPECT_TRUE(testproj::MyObject::create(db, 15, std::nullopt, {1}));
+		return std::unexpected{"Failed"};
+	});
+	EXPECT_FALSE(error_result);
+	EXPECT_EQ(error_result.error().category, genORM::error_category::application);
+	EXPECT_EQ(error_result.error().message, "Failed");
+	EXPECT_FALSE(db->is_in_transaction());
+	EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db, 15)->empty());
+
+	// An exception rolls back and propagates
+	EXPECT_THROW(db->execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_TRUE(testproj::MyObject::create(db, 15, std::nullopt, {1}));
+		throw std::runtime_error("Failed");
+	}), std::runtime_error);
+	EXPECT_FALSE(db->is_in_transaction());
+	EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db, 15)->empty());
+
+	// An explicit rollback discards the changes and fails the transaction
+	const auto rollback_result = db->execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_TRUE(testproj::MyObject::create(db, 15, std::nullopt, {1}));
+		EXPECT_TRUE(db.rollback_transaction());
+		return {};
+	});
+	EXPECT_FALSE(rollback_result);
+	EXPECT_FALSE(db->is_in_transaction());
+	EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db, 15)->empty());
+	EXPECT_TRUE(db->rollback_transaction());
+
+	// Schema initialization nests in an active transaction, migrations refuse to run in one
+	std::filesystem::remove("test2.db");
+	auto db_2 = genORM::database::open_or_create("test2.db");
+	EXPECT_TRUE(db_2->set_user_version(1));
+	EXPECT_TRUE(db_2->execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_FALSE(testproj::migrate(db));
+		return testproj::initialize_schema(db);
+	}));
+	EXPECT_TRUE(db_2->has_table("MyObject"));
+	std::filesystem::remove("test2.db");
+}
+
+TEST(genORM, nested_transaction) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+
+	// A failing inner scope only discards its own changes
+	EXPECT_TRUE(db->execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_TRUE(testproj::MyObject::create(db, 15, std::nullopt, {1}));
+		const auto inner_result = db.execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+			EXPECT_EQ(db.get_transaction_depth(), 2);
+			EXPECT_TRUE(testproj::MyObject::create(db, 16, std::nullopt, {2}));
+			return std::unexpected{"Failed"};
+		});
+		EXPECT_FALSE(inner_result);
+		EXPECT_EQ(db.get_transaction_depth(), 1);
+		EXPECT_TRUE(db.execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+			EXPECT_TRUE(testproj::MyObject::create(db, 17, std::nullopt, {3}));
+			return {};
+		}));
+		EXPECT_THROW(db.execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+			EXPECT_TRUE(testproj::MyObject::create(db, 18, std::nullopt, {4}));
+			throw std::runtime_error("Failed");
+		}), std::runtime_error);
+		EXPECT_FALSE(db.execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+			EXPECT_TRUE(testproj::MyObject::create(db, 19, std::nullopt, {5}));
+			return db.rollback_transaction();
+		}));
+		EXPECT_EQ(db.get_transaction_depth(), 1);
+		return {};
+	}));
+	EXPECT_EQ(db->get_transaction_depth(), 0);
+	EXPECT_FALSE(db->is_in_transaction());
+	for (const auto& [i, count] : {std::pair{15, 1}, {16, 0}, {17, 1}, {18, 0}, {19, 0}}) {
+		EXPECT_EQ(testproj::MyObject::find_all_by_i(*db, i)->size(), count);
+	}
+
+	// Rolling back the outer scope discards the changes of committed inner scopes
+	EXPECT_FALSE(db->execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_TRUE(db.execute_transaction<void>([](genORM::database& db) -> std::expected<void, genORM::error> {
+			EXPECT_TRUE(testproj::MyObject::create(db, 20, std::nullopt, {6}));
+			return {};
+		}));
+		return std::unexpected{"Failed"};
+	}));
+	EXPECT_EQ(db->get_transaction_depth(), 0);
+	EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db, 20)->empty());
+}
+
+TEST(genORM, transaction_mode) {
+	std::filesystem::remove("test.db");
+	auto db_1 = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db_1));
+	auto db_2 = genORM::database::open("test.db");
+
+	// A deferred transaction holds no locks until it first accesses the database
+	EXPECT_TRUE(db_1->execute_transaction<void>([&](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_TRUE(testproj::MyObject::create(*db_2, 15, std::nullopt, {1}));
+		EXPECT_TRUE(testproj::MyObject::find_by_rowid(db, 1));
+		return {};
+	}, genORM::transaction_mode::deferred));
+
+	// An immediate transaction holds the write lock before its first write, readers can proceed
+	EXPECT_TRUE(db_1->execute_transaction<void>([&](genORM::database& db) -> std::expected<void, genORM::error> {
+		EXPECT_FALSE(testproj::MyObject::create(*db_2, 16, std::nullopt, {2}));
+		EXPECT_TRUE(testproj::MyObject::find_by_rowid(*db_2, 1));
+		EXPECT_TRUE(testproj::MyObject::create(db, 17, std::nullopt, {3}));
+		return {};
+	}, genORM::transaction_mode::immediate));
+
+	// An exclusive transaction blocks readers as well
+	EXPECT_TRUE(db_1->execute_transaction<void>([&](genORM::database&) -> std::expected<void, genORM::error> {
+		EXPECT_FALSE(testproj::MyObject::create(*db_2, 18, std::nullopt, {4}));
+		EXPECT_FALSE(testproj::MyObject::find_by_rowid(*db_2, 1));
+		return {};
+	}, genORM::transaction_mode::exclusive));
+
+	EXPECT_EQ(testproj::MyObject::find_all_by_i(*db_2, 15)->size(), 1);
+	EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db_2, 16)->empty());
+	EXPECT_EQ(testproj::MyObject::find_all_by_i(*db_2, 17)->size(), 1);
+	EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db_2, 18)->empty());
+}
+
+TEST(genORM, busy_handling) {
+	std::filesystem::remove("test.db");
+	auto db_1 = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db_1));
+	auto db_2 = genORM::database::open("test.db");
+	auto db_3 = genORM::database::open("test.db", {.busy = {.timeout = std::chrono::milliseconds{5000}}});
+	auto db_4 = genORM::database::open("test.db", {.busy = {.max_retries = 20, .initial_backoff = std::chrono::milliseconds{5}, .max_backoff = std::chrono::milliseconds{50}}});
+
+	// Holds the write lock for a while on another thread
+	const auto hold_write_lock = [&] {
+		std::promise<void> locked;
+		auto released = std::async(std::launch::async, [&] {
+			return db_1->execute_transaction<void>([&](genORM::database&) -> std::expected<void, genORM::error> {
+				locked.set_value();
+				std::this_thread::sleep_for(std::chrono::milliseconds{200});
+				return {};
+			}, genORM::transaction_mode::exclusive);
+		});
+		locked.get_future().wait();
+		return released;
+	};
+
+	// Without busy handling, the call fails immediately
+	auto released = hold_write_lock();
+	EXPECT_FALSE(testproj::MyObject::create(*db_2, 15, std::nullopt, {1}));
+	EXPECT_TRUE(released.get());
+
+	// The busy timeout waits until the lock is released
+	released = hold_write_lock();
+	EXPECT_TRUE(testproj::MyObject::create(*db_3, 16, std::nullopt, {2}));
+	EXPECT_TRUE(released.get());
+
+	// So does retrying with backoff
+	released = hold_write_lock();
+	EXPECT_TRUE(testproj::MyObject::create(*db_4, 17, std::nullopt, {3}));
+	EXPECT_TRUE(released.get());
+
+	EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db_1, 15)->empty());
+	EXPECT_EQ(testproj::MyObject::find_all_by_i(*db_1, 16)->size(), 1);
+	EXPECT_EQ(testproj::MyObject::find_all_by_i(*db_1, 17)->size(), 1);
+
+	// Ranges read lazily, their first read follows the same policy
+	auto unhandled = testproj::MyObject::iterate_all_by_i(*db_2, 17);
+	ASSERT_TRUE(unhandled);
+	released = hold_write_lock();
+	auto unhandled_it = unhandled->begin();
+	ASSERT_NE(unhandled_it, unhandled->end());
+	ASSERT_FALSE(*unhandled_it);
+	EXPECT_EQ(unhandled_it->error().category, genORM::error_category::busy);
+	EXPECT_TRUE(released.get());
+
+	auto retried = testproj::MyObject::iterate_all_by_i(*db_4, 17);
+	ASSERT_TRUE(retried);
+	released = hold_write_lock();
+	auto retried_it = retried->begin();
+	ASSERT_NE(retried_it, retried->end());
+	ASSERT_TRUE(*retried_it);
+	EXPECT_EQ((*retried_it)->get_ba(), std::vector<uint8_t>{3});
+	EXPECT_TRUE(released.get());
+}
+
+TEST(genORM, open_options) {
+	{
+		// In-memory databases are private to the connection unless the cache is shared
+		auto db_1 = genORM::database::open("memdb", {.in_memory = true, .shared_cache = true});
+		ASSERT_TRUE(db_1);
+		ASSERT_TRUE(testproj::initialize_schema(*db_1));
+		EXPECT_TRUE(testproj::MyObject::create(*db_1, 15, std::nullopt, {1}));
+		auto db_2 = genORM::database::open("memdb", {.in_memory = true, .shared_cache = true});
+		EXPECT_EQ(testproj::MyObject::find_all_by_i(*db_2, 15)->size(), 1);
+		auto db_3 = genORM::database::open("memdb", {.in_memory = true});
+		EXPECT_TRUE(testproj::MyObject::find_all_by_i(*db_3, 15)->empty());
+		EXPECT_FALSE(std::filesystem::exists("memdb"));
+	}
+	{
+		std::filesystem::remove("test.db");
+		std::filesystem::remove("test.db-wal");
+		EXPECT_FALSE(genORM::database::open("test.db", {.read_only = true}));
+		auto db = genORM::database::open("test.db", {.create = true, .wal = true, .synchronous = genORM::synchronous_level::normal, .pragmas = {"user_version = 7"}});
+		ASSERT_TRUE(db);
+		EXPECT_EQ(db->get_user_version(), 7);
+		ASSERT_TRUE(testproj::initialize_schema(*db));
+		EXPECT_TRUE(testproj::MyObject::create(*db, 15, std::nullopt, {1}));
+		EXPECT_TRUE(std::filesystem::exists("test.db-wal"));
+
+		// Read-only connections can read but not write, URIs can restrict access as well
+		auto read_only_db = genORM::database::open("test.db", {.read_only = true});
+		ASSERT_TRUE(read_only_db);
+		EXPECT_EQ(testproj::MyObject::find_all_by_i(*read_only_db, 15)->size(), 1);
+		EXPECT_FALSE(testproj::MyObject::create(*read_only_db, 16, std::nullopt, {2}));
+		EXPECT_FALSE(genORM::database::open("file:test.db?mode=ro"));
+		EXPECT_TRUE(genORM::database::open("file:test.db?mode=ro", {.read_only = true}));
+		EXPECT_FALSE(genORM::database::open("test.db", {.pragmas = {"no_such_syntax ="}}));
+	}
+	{
+		// Without foreign key enforcement, dangling references can be stored
+		auto db = genORM::database::open(":memory:", {.foreign_keys = false});
+		ASSERT_TRUE(db);
+		ASSERT_TRUE(testproj::initialize_schema(*db));
+		EXPECT_TRUE(testproj::MyFourthObject::create(*db, 42, std::nullopt));
+	}
+}
+
+TEST(genORM, error) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+
+	const auto missing_table = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	ASSERT_FALSE(missing_table);
+	EXPECT_EQ(missing_table.error().category, genORM::error_category::schema_mismatch);
+	EXPECT_EQ(missing_table.error().result_code, SQLITE_ERROR);
+	EXPECT_EQ(missing_table.error().message, "no such table: MyObject");
+	EXPECT_TRUE(missing_table.error().statement.starts_with("INSERT INTO MyObject"));
+	EXPECT_EQ(missing_table.error().to_string(), "no such table: MyObject in statement: " + missing_table.error().statement);
+
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_TRUE(testproj::MySecondObject::create(*db, 1, std::nullopt, "s", std::nullopt, 0.0f, std::nullopt, false, std::nullopt));
+	const auto duplicate = testproj::MySecondObject::create(*db, 1, std::nullopt, "s", std::nullopt, 0.0f, std::nullopt, false, std::nullopt);
+	ASSERT_FALSE(duplicate);
+	EXPECT_EQ(duplicate.error().category, genORM::error_category::unique_violation);
+	EXPECT_EQ(duplicate.error().result_code, SQLITE_CONSTRAINT);
+	EXPECT_EQ(duplicate.error().extended_result_code, SQLITE_CONSTRAINT_UNIQUE);
+	EXPECT_EQ(duplicate.error().message, "UNIQUE constraint failed: MySecondObject.l");
+
+	auto obj = testproj::MyObject::create(*db, 15, std::nullopt, {1});
+	EXPECT_TRUE(obj->remove());
+	const auto removed = obj->save();
+	ASSERT_FALSE(removed);
+	EXPECT_EQ(removed.error().category, genORM::error_category::not_found);
+	EXPECT_EQ(removed.error().result_code, SQLITE_OK);
+	EXPECT_TRUE(removed.error().statement.empty());
+
+	const auto syntax = db->execute("SELEKT 1;");
+	ASSERT_FALSE(syntax);
+	EXPECT_EQ(syntax.error().category, genORM::error_category::database);
+	EXPECT_EQ(syntax.error().statement, "SELEKT 1;");
+
+	auto db_2 = genORM::database::open("test.db");
+	EXPECT_TRUE(db->execute_transaction<void>([&](genORM::database&) -> std::expected<void, genORM::error> {
+		const auto locked = testproj::MyObject::create(*db_2, 16, std::nullopt, {2});
+		EXPECT_FALSE(locked);
+		EXPECT_EQ(locked.error().category, genORM::error_category::busy);
+		return {};
+	}, genORM::transaction_mode::exclusive));
+}
+
+static_assert(std::ranges::input_range<genORM::object_range<testproj::MyObject>&>);
+template <typename T>
+concept iterable_as_temporary = requires(T&& range) { std::move(range).begin(); };
+static_assert(not iterable_as_temporary<genORM::object_range<testproj::MyObject>>);
+
+TEST(genORM, iterate_all) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+
+	// Before initialization, the range is empty
+	auto missing_table = testproj::MyObject::iterate_all_by_i(*db, 15);
+	ASSERT_TRUE(missing_table);
+	EXPECT_EQ(missing_table->begin(), missing_table->end());
+
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	for (uint8_t b = 0; b < 5; ++b) {
+		EXPECT_TRUE(testproj::MyObject::create(*db, 15, b, {b}));
+	}
+	EXPECT_TRUE(testproj::MyObject::create(*db, 16, std::nullopt, {}));
+
+	auto objects = testproj::MyObject::iterate_all_by_i(*db, 15);
+	ASSERT_TRUE(objects);
+	int32_t count = 0;
+	for (auto& obj : *objects) {
+		ASSERT_TRUE(obj);
+		EXPECT_EQ(obj->get_i(), 15);
+		EXPECT_EQ(obj->get_io(), count);
+		EXPECT_EQ(obj->get_ba(), std::vector<uint8_t>{static_cast<uint8_t>(count)});
+		++count;
+	}
+	EXPECT_EQ(count, 5);
+
+	// Several cursors can be alive at once, and stopping early is fine
+	auto first = testproj::MyObject::iterate_all_by_i(*db, 15);
+	auto second = testproj::MyObject::iterate_all_by_i(*db, 15);
+	auto first_it = first->begin();
+	auto second_it = second->begin();
+	++second_it;
+	EXPECT_EQ((*first_it)->get_io(), 0);
+	EXPECT_EQ((*second_it)->get_io(), 1);
+	EXPECT_EQ(testproj::MyObject::find_all_by_i(*db, 15)->size(), 5);
+}
+
+TEST(genORM, iterate_all_decode_error) {
+	std::filesystem::remove("test.db");
+	auto db = genORM::database::open_or_create("test.db");
+	ASSERT_TRUE(testproj::initialize_schema(*db));
+	EXPECT_TRUE(testproj::MyThirdObject::create(*db, testproj::Color::RED, std::nullopt, {}, std::nullopt));
+	EXPECT_TRUE(testproj::MyThirdObject::create(*db, testproj::Color::RED, testproj::Shape::SQUARE, {}, std::nullopt));
+	EXPECT_TRUE(testproj::MyThirdObject::create(*db, testproj::Color::RED, testproj::Shape::CIRCLE, {}, std::nullopt));
+	EXPECT_TRUE(db->execute("UPDATE MyThirdObject SET sh = 'TRIANGLE' WHERE __id = 2;"));
+
+	// The row that cannot be decoded ends the iteration
+	auto objects = testproj::MyThirdObject::iterate_all_by_c(*db, testproj::Color::RED);
+	ASSERT_TRUE(objects);
+	std::vector<std::expected<testproj::MyThirdObject, genORM::error>> elements;
+	for (auto& obj : *objects) {
+		elements.emplace_back(std::move(obj));
+	}
+	ASSERT_EQ(elements.size(), 2);
+	EXPECT_TRUE(elements[0]);
+	EXPECT_FALSE(elements[1]);
+	EXPECT_EQ(elements[1].error().category, genORM::error_category::invalid_value);
+}